@@ -0,0 +1,1215 @@
+use std::{fs::File, io::Write, time::Duration};
+
+use anyhow::{anyhow, Context, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use itertools::Itertools;
+
+use crate::config::{Config, DEFAULT_CONFIG_PATH};
+use crate::csv_io::{export_csv, import_csv};
+use crate::db::{
+    copy_database, sqlite::SqliteDatabase, Database, JSONFileDatabase, JiraDatabase,
+    DEFAULT_LOCK_TIMEOUT,
+};
+use crate::filter::Filter;
+use crate::html::export_html;
+use crate::jira::import_jira;
+use crate::models::{validate_label, Epic, ItemKind, Priority, Status, Story, User};
+use crate::output::{
+    write_epics, write_stories, write_users, EpicRecord, OutputFormat, StoryRecord,
+};
+use crate::report::write_markdown;
+use crate::ui::pages::page_helpers::has_label;
+
+#[derive(Parser, Debug)]
+#[command(version, about = "JIRA-like issue tracker for the command line")]
+#[command(after_help = "Run without a subcommand to start the interactive menu.")]
+pub struct Cli {
+    /// Path to the database file [default: data/db.json or data/db.sqlite]
+    #[arg(long, global = true)]
+    pub db: Option<String>,
+    /// Storage backend of the database file
+    #[arg(long, global = true, value_enum, default_value_t = Backend::Json)]
+    pub backend: Backend,
+    /// Seconds to wait for another process to release the database lock
+    #[arg(long, global = true, default_value_t = DEFAULT_LOCK_TIMEOUT.as_secs())]
+    pub lock_timeout: u64,
+    /// Output format of list commands
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Table)]
+    pub format: OutputFormat,
+    /// Path to the config file naming the current user
+    #[arg(long, global = true, default_value = DEFAULT_CONFIG_PATH)]
+    pub config: String,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// Single JSON file rewritten on every change
+    Json,
+    /// SQLite database with tables for epics and stories
+    Sqlite,
+}
+
+impl Cli {
+    pub fn db_path(&self) -> String {
+        self.db.clone().unwrap_or_else(|| match self.backend {
+            Backend::Json => "data/db.json".to_owned(),
+            Backend::Sqlite => "data/db.sqlite".to_owned(),
+        })
+    }
+    pub fn open_database(&self) -> Result<JiraDatabase> {
+        let lock_timeout = Duration::from_secs(self.lock_timeout);
+        match self.backend {
+            Backend::Json => Ok(JiraDatabase::new(self.db_path(), lock_timeout)),
+            Backend::Sqlite => Ok(JiraDatabase::with_database(Box::new(SqliteDatabase::open(
+                self.db_path(),
+                lock_timeout,
+            )?))),
+        }
+    }
+}
+
+/// Order of the stories of an epic in `story list`.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorySort {
+    /// Order in which the stories were added to their epic
+    Insertion,
+    /// Highest priority first, equal priorities keep their insertion order
+    Priority,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Create, list, update and delete epics
+    Epic {
+        #[command(subcommand)]
+        command: EpicCommand,
+    },
+    /// Create, list, update and delete stories
+    Story {
+        #[command(subcommand)]
+        command: StoryCommand,
+    },
+    /// Register, list and remove users
+    User {
+        #[command(subcommand)]
+        command: UserCommand,
+    },
+    /// Revert the latest change
+    Undo,
+    /// Apply the latest undone change again
+    Redo,
+    /// Write all epics and stories to a file for use elsewhere
+    Export {
+        #[command(subcommand)]
+        command: ExportCommand,
+    },
+    /// Create epics and stories from a file
+    Import {
+        #[command(subcommand)]
+        command: ImportCommand,
+    },
+    /// Summarize all epics and their stories
+    Report {
+        #[command(subcommand)]
+        command: ReportCommand,
+    },
+    /// Copy a JSON database into a SQLite database
+    Migrate {
+        #[arg(long, default_value = "data/db.json")]
+        from: String,
+        #[arg(long, default_value = "data/db.sqlite")]
+        to: String,
+        /// Overwrite a SQLite database that already contains items
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum EpicCommand {
+    /// Create a new epic and print its id
+    Create {
+        #[arg(long)]
+        name: String,
+        #[arg(long, default_value = "")]
+        description: String,
+        /// highest, high, medium, low or lowest
+        #[arg(long, default_value = "medium")]
+        priority: Priority,
+        /// Label of the epic, may be repeated
+        #[arg(long = "label", value_parser = parse_label)]
+        labels: Vec<String>,
+    },
+    /// List all epics
+    List {
+        /// Only list epics with this label
+        #[arg(long)]
+        label: Option<String>,
+        /// Only list epics matching a filter expression, e.g. 'status:open owner:alice'
+        #[arg(long = "where", value_parser = parse_epic_filter)]
+        filter: Option<Filter>,
+    },
+    /// Change the name and/or description of an epic
+    Edit {
+        epic_id: u32,
+        #[arg(long)]
+        name: Option<String>,
+        #[arg(long)]
+        description: Option<String>,
+    },
+    /// Set the status of an epic (open, in-progress, resolved, closed)
+    Status { epic_id: u32, status: Status },
+    /// Set the priority of an epic (highest, high, medium, low, lowest)
+    Priority { epic_id: u32, priority: Priority },
+    /// Make a registered user the owner of an epic
+    Assign { epic_id: u32, username: String },
+    /// Remove the owner of an epic
+    Unassign { epic_id: u32 },
+    /// Add a label to an epic
+    Label { epic_id: u32, label: String },
+    /// Remove a label from an epic
+    Unlabel { epic_id: u32, label: String },
+    /// Delete an epic together with all of its stories
+    Delete { epic_id: u32 },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum StoryCommand {
+    /// Create a new story in an epic and print its id
+    Create {
+        #[arg(long)]
+        epic: u32,
+        #[arg(long)]
+        name: String,
+        #[arg(long, default_value = "")]
+        description: String,
+        /// highest, high, medium, low or lowest
+        #[arg(long, default_value = "medium")]
+        priority: Priority,
+        /// Label of the story, may be repeated
+        #[arg(long = "label", value_parser = parse_label)]
+        labels: Vec<String>,
+    },
+    /// List the stories of one epic, or all stories
+    List {
+        #[arg(long)]
+        epic: Option<u32>,
+        /// Order of the stories within each epic
+        #[arg(long, value_enum, default_value_t = StorySort::Insertion)]
+        sort: StorySort,
+        /// Only list stories assigned to this user
+        #[arg(long, conflicts_with = "mine")]
+        assignee: Option<String>,
+        /// Only list stories assigned to the current user
+        #[arg(long)]
+        mine: bool,
+        /// Only list stories with this label
+        #[arg(long)]
+        label: Option<String>,
+        /// Only list stories matching a filter expression, e.g. 'status:InProgress name~"login"'
+        #[arg(long = "where", value_parser = parse_story_filter)]
+        filter: Option<Filter>,
+    },
+    /// Change the name and/or description of a story
+    Edit {
+        story_id: u32,
+        #[arg(long)]
+        name: Option<String>,
+        #[arg(long)]
+        description: Option<String>,
+    },
+    /// Set the status of a story (open, in-progress, resolved, closed)
+    Status { story_id: u32, status: Status },
+    /// Set the priority of a story (highest, high, medium, low, lowest)
+    Priority { story_id: u32, priority: Priority },
+    /// Assign a story to a registered user
+    Assign { story_id: u32, username: String },
+    /// Remove the assignee of a story
+    Unassign { story_id: u32 },
+    /// Add a label to a story
+    Label { story_id: u32, label: String },
+    /// Remove a label from a story
+    Unlabel { story_id: u32, label: String },
+    /// Add a comment to the discussion of a story
+    Comment { story_id: u32, body: String },
+    /// Move a story to another epic
+    Move {
+        story_id: u32,
+        #[arg(long)]
+        to: u32,
+    },
+    /// Delete a story
+    Delete { story_id: u32 },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum UserCommand {
+    /// Register a new user
+    Add {
+        username: String,
+        /// Display name [default: the username]
+        #[arg(long)]
+        name: Option<String>,
+    },
+    /// List all registered users
+    List,
+    /// Remove a user that nothing is assigned to anymore
+    Remove { username: String },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ExportCommand {
+    /// One row per story with its epic id, epic name and status
+    Csv {
+        /// File to write [default: stdout]
+        #[arg(long, short)]
+        output: Option<String>,
+    },
+    /// Self-contained page with an epics index and a story table per epic
+    Html {
+        /// File to write [default: stdout]
+        #[arg(long, short)]
+        output: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ImportCommand {
+    /// Rows with an epic_name and story columns as written by `export csv`, ids are ignored
+    Csv { file: String },
+    /// Issue export of Jira as JSON: epics with their child issues as stories
+    Jira { file: String },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ReportCommand {
+    /// Every epic as a heading with its description and a checklist of its stories
+    Markdown {
+        /// Only list stories with this status, can be repeated
+        #[arg(long)]
+        status: Vec<Status>,
+        /// File to write [default: stdout]
+        #[arg(long, short)]
+        output: Option<String>,
+    },
+}
+
+fn parse_label(label: &str) -> std::result::Result<String, String> {
+    validate_label(label).map(|_| label.to_owned())
+}
+
+fn parse_epic_filter(input: &str) -> std::result::Result<Filter, String> {
+    Filter::parse(input, ItemKind::Epic)
+}
+
+fn parse_story_filter(input: &str) -> std::result::Result<Filter, String> {
+    Filter::parse(input, ItemKind::Story)
+}
+
+pub fn run(cli: Cli, db: &JiraDatabase, out: &mut dyn Write) -> Result<()> {
+    let lock_timeout = Duration::from_secs(cli.lock_timeout);
+    match cli.command {
+        Some(Command::Epic { command }) => run_epic_command(command, cli.format, db, out),
+        Some(Command::Story { command }) => {
+            run_story_command(command, cli.format, &cli.config, db, out)
+        }
+        Some(Command::User { command }) => run_user_command(command, cli.format, db, out),
+        Some(Command::Undo) => {
+            match db.undo()? {
+                Some(description) => writeln!(out, "undid {}", description)?,
+                None => writeln!(out, "nothing to undo")?,
+            }
+            Ok(())
+        }
+        Some(Command::Redo) => {
+            match db.redo()? {
+                Some(description) => writeln!(out, "redid {}", description)?,
+                None => writeln!(out, "nothing to redo")?,
+            }
+            Ok(())
+        }
+        Some(Command::Export { command }) => match command {
+            ExportCommand::Csv { output } => {
+                let db_state = db.read_db()?;
+                write_output(output.as_deref(), out, |out| export_csv(&db_state, out))
+                    .with_context(|| anyhow!("failed to export csv"))
+            }
+            ExportCommand::Html { output } => {
+                let db_state = db.read_db()?;
+                write_output(output.as_deref(), out, |out| export_html(&db_state, out))
+                    .with_context(|| anyhow!("failed to export html"))
+            }
+        },
+        Some(Command::Import { command }) => match command {
+            ImportCommand::Csv { file } => {
+                let mut input =
+                    File::open(&file).with_context(|| anyhow!("failed to open {}", file))?;
+                let report = import_csv(db, &mut input)
+                    .with_context(|| anyhow!("failed to import {}", file))?;
+                for (line, error) in &report.errors {
+                    writeln!(out, "line {}: {}", line, error)?;
+                }
+                writeln!(
+                    out,
+                    "imported {} epics and {} stories",
+                    report.epics, report.stories
+                )?;
+                if !report.errors.is_empty() {
+                    return Err(anyhow!(
+                        "{} rows could not be imported",
+                        report.errors.len()
+                    ));
+                }
+                Ok(())
+            }
+            ImportCommand::Jira { file } => {
+                let status_map = Config::load(&cli.config)?.jira_statuses;
+                let mut input =
+                    File::open(&file).with_context(|| anyhow!("failed to open {}", file))?;
+                let report = import_jira(db, &mut input, &status_map)
+                    .with_context(|| anyhow!("failed to import {}", file))?;
+                for (key, error) in &report.errors {
+                    writeln!(out, "{}: {}", key, error)?;
+                }
+                writeln!(
+                    out,
+                    "imported {} epics and {} stories",
+                    report.epics, report.stories
+                )?;
+                if !report.errors.is_empty() {
+                    return Err(anyhow!(
+                        "{} issues could not be imported",
+                        report.errors.len()
+                    ));
+                }
+                Ok(())
+            }
+        },
+        Some(Command::Report { command }) => match command {
+            ReportCommand::Markdown { status, output } => {
+                let db_state = db.read_db()?;
+                write_output(output.as_deref(), out, |out| {
+                    write_markdown(&db_state, &status, out)
+                })
+                .with_context(|| anyhow!("failed to write markdown report"))
+            }
+        },
+        Some(Command::Migrate { from, to, force }) => {
+            let mut json = JSONFileDatabase::new(from);
+            json.lock_timeout = lock_timeout;
+            let sqlite = SqliteDatabase::open(to, lock_timeout)?;
+            let existing = sqlite.read_db()?;
+            if !force && (!existing.epics.is_empty() || !existing.stories.is_empty()) {
+                return Err(anyhow!(
+                    "{} already contains items, use --force to overwrite it",
+                    sqlite.file_path
+                ));
+            }
+            let db_state = copy_database(&json, &sqlite)
+                .with_context(|| anyhow!("failed to migrate {}", json.file_path))?;
+            writeln!(
+                out,
+                "migrated {} epics and {} stories to {}",
+                db_state.epics.len(),
+                db_state.stories.len(),
+                sqlite.file_path
+            )?;
+            Ok(())
+        }
+        None => Ok(()),
+    }
+}
+
+fn run_epic_command(
+    command: EpicCommand,
+    format: OutputFormat,
+    db: &JiraDatabase,
+    out: &mut dyn Write,
+) -> Result<()> {
+    match command {
+        EpicCommand::Create {
+            name,
+            description,
+            priority,
+            labels,
+        } => {
+            let mut epic = Epic::new(name, description);
+            epic.priority = priority;
+            epic.labels.extend(labels);
+            let epic_id = db
+                .create_epic(epic)
+                .with_context(|| anyhow!("failed to create epic"))?;
+            writeln!(out, "{}", epic_id)?;
+        }
+        EpicCommand::List { label, filter } => {
+            let epics = db.list_epics()?;
+            let records: Vec<EpicRecord> = epics
+                .keys()
+                .filter(|key| has_label(&epics[key].labels, label.as_deref()))
+                .filter(|key| {
+                    filter
+                        .as_ref()
+                        .is_none_or(|filter| filter.matches_epic(**key, &epics[key]))
+                })
+                .sorted()
+                .map(|key| EpicRecord {
+                    id: *key,
+                    epic: &epics[key],
+                })
+                .collect();
+            write_epics(out, format, &records)?;
+        }
+        EpicCommand::Edit {
+            epic_id,
+            name,
+            description,
+        } => {
+            let epic = db
+                .get_epic(epic_id)?
+                .ok_or_else(|| anyhow!("could not find epic with id {}", epic_id))?;
+            db.update_epic_details(
+                epic_id,
+                name.unwrap_or(epic.name),
+                description.unwrap_or(epic.description),
+            )
+            .with_context(|| anyhow!("failed to update epic"))?;
+        }
+        EpicCommand::Status { epic_id, status } => {
+            let closing = status == Status::Closed;
+            db.update_epic_status(epic_id, status)
+                .with_context(|| anyhow!("failed to update epic status"))?;
+            let open_stories = if closing {
+                db.open_stories(epic_id)?
+            } else {
+                vec![]
+            };
+            if !open_stories.is_empty() {
+                eprintln!(
+                    "warning: closed epic {} with stories {} still open",
+                    epic_id,
+                    open_stories.iter().join(", ")
+                );
+            }
+        }
+        EpicCommand::Priority { epic_id, priority } => {
+            db.update_epic_priority(epic_id, priority)
+                .with_context(|| anyhow!("failed to update epic priority"))?;
+        }
+        EpicCommand::Assign { epic_id, username } => {
+            db.assign_epic(epic_id, Some(username))
+                .with_context(|| anyhow!("failed to assign epic"))?;
+        }
+        EpicCommand::Unassign { epic_id } => {
+            db.assign_epic(epic_id, None)
+                .with_context(|| anyhow!("failed to unassign epic"))?;
+        }
+        EpicCommand::Label { epic_id, label } => {
+            db.label_epic(epic_id, &label, true)
+                .with_context(|| anyhow!("failed to add epic label"))?;
+        }
+        EpicCommand::Unlabel { epic_id, label } => {
+            db.label_epic(epic_id, &label, false)
+                .with_context(|| anyhow!("failed to remove epic label"))?;
+        }
+        EpicCommand::Delete { epic_id } => {
+            db.delete_epic(epic_id)
+                .with_context(|| anyhow!("failed to delete epic"))?;
+        }
+    }
+    Ok(())
+}
+
+fn run_story_command(
+    command: StoryCommand,
+    format: OutputFormat,
+    config_path: &str,
+    db: &JiraDatabase,
+    out: &mut dyn Write,
+) -> Result<()> {
+    match command {
+        StoryCommand::Create {
+            epic,
+            name,
+            description,
+            priority,
+            labels,
+        } => {
+            let mut story = Story::new(name, description);
+            story.priority = priority;
+            story.labels.extend(labels);
+            let story_id = db
+                .create_story(story, epic)
+                .with_context(|| anyhow!("failed to create story"))?;
+            writeln!(out, "{}", story_id)?;
+        }
+        StoryCommand::List {
+            epic,
+            sort,
+            assignee,
+            mine,
+            label,
+            filter,
+        } => {
+            let assignee = if mine {
+                let user = Config::load(config_path)?.current_user();
+                Some(user.ok_or_else(|| {
+                    anyhow!("no current user, set \"user\" in {} or $USER", config_path)
+                })?)
+            } else {
+                assignee
+            };
+            let db_state = db.read_db()?;
+            let mut story_ids: Vec<(u32, u32)> = match epic {
+                Some(epic_id) => db_state
+                    .epics
+                    .get(&epic_id)
+                    .ok_or_else(|| anyhow!("could not find epic with id {}", epic_id))?
+                    .stories
+                    .iter()
+                    .map(|story_id| (epic_id, *story_id))
+                    .collect(),
+                None => db_state
+                    .epics
+                    .iter()
+                    .flat_map(|(epic_id, epic)| {
+                        epic.stories.iter().map(|story_id| (*epic_id, *story_id))
+                    })
+                    .sorted_by_key(|(_, story_id)| *story_id)
+                    .collect(),
+            };
+            if let Some(assignee) = &assignee {
+                story_ids.retain(|(_, story_id)| {
+                    db_state.stories[story_id].assignee.as_ref() == Some(assignee)
+                });
+            }
+            story_ids.retain(|(_, story_id)| {
+                has_label(&db_state.stories[story_id].labels, label.as_deref())
+            });
+            if let Some(filter) = &filter {
+                story_ids.retain(|(epic_id, story_id)| {
+                    filter.matches_story(*story_id, *epic_id, &db_state.stories[story_id])
+                });
+            }
+            if sort == StorySort::Priority {
+                story_ids.sort_by_key(|(_, story_id)| db_state.stories[story_id].priority);
+            }
+            let records: Vec<StoryRecord> = story_ids
+                .into_iter()
+                .map(|(epic_id, story_id)| StoryRecord {
+                    id: story_id,
+                    epic_id,
+                    story: &db_state.stories[&story_id],
+                })
+                .collect();
+            write_stories(out, format, &records)?;
+        }
+        StoryCommand::Edit {
+            story_id,
+            name,
+            description,
+        } => {
+            let story = db
+                .get_story(story_id)?
+                .ok_or_else(|| anyhow!("could not find story with id {}", story_id))?;
+            db.update_story_details(
+                story_id,
+                name.unwrap_or(story.name),
+                description.unwrap_or(story.description),
+            )
+            .with_context(|| anyhow!("failed to update story"))?;
+        }
+        StoryCommand::Status { story_id, status } => {
+            db.update_story_status(story_id, status)
+                .with_context(|| anyhow!("failed to update story status"))?;
+        }
+        StoryCommand::Priority { story_id, priority } => {
+            db.update_story_priority(story_id, priority)
+                .with_context(|| anyhow!("failed to update story priority"))?;
+        }
+        StoryCommand::Assign { story_id, username } => {
+            db.assign_story(story_id, Some(username))
+                .with_context(|| anyhow!("failed to assign story"))?;
+        }
+        StoryCommand::Unassign { story_id } => {
+            db.assign_story(story_id, None)
+                .with_context(|| anyhow!("failed to unassign story"))?;
+        }
+        StoryCommand::Label { story_id, label } => {
+            db.label_story(story_id, &label, true)
+                .with_context(|| anyhow!("failed to add story label"))?;
+        }
+        StoryCommand::Unlabel { story_id, label } => {
+            db.label_story(story_id, &label, false)
+                .with_context(|| anyhow!("failed to remove story label"))?;
+        }
+        StoryCommand::Comment { story_id, body } => {
+            db.add_comment(story_id, body)
+                .with_context(|| anyhow!("failed to add comment"))?;
+        }
+        StoryCommand::Move { story_id, to } => {
+            let epic_id = find_epic_of_story(db, story_id)?;
+            db.move_story(story_id, epic_id, to)
+                .with_context(|| anyhow!("failed to move story"))?;
+        }
+        StoryCommand::Delete { story_id } => {
+            let epic_id = find_epic_of_story(db, story_id)?;
+            db.delete_story(epic_id, story_id)
+                .with_context(|| anyhow!("failed to delete story"))?;
+        }
+    }
+    Ok(())
+}
+
+fn run_user_command(
+    command: UserCommand,
+    format: OutputFormat,
+    db: &JiraDatabase,
+    out: &mut dyn Write,
+) -> Result<()> {
+    match command {
+        UserCommand::Add { username, name } => {
+            let name = name.unwrap_or_else(|| username.clone());
+            db.add_user(User::new(username, name))
+                .with_context(|| anyhow!("failed to add user"))?;
+        }
+        UserCommand::List => write_users(out, format, &db.list_users()?)?,
+        UserCommand::Remove { username } => {
+            db.remove_user(&username)
+                .with_context(|| anyhow!("failed to remove user"))?;
+        }
+    }
+    Ok(())
+}
+
+/// Runs `write` on a new file at `output`, or on `out` when no file is given.
+fn write_output(
+    output: Option<&str>,
+    out: &mut dyn Write,
+    write: impl FnOnce(&mut dyn Write) -> Result<()>,
+) -> Result<()> {
+    match output {
+        Some(file_path) => {
+            let mut file = File::create(file_path)
+                .with_context(|| anyhow!("failed to create {}", file_path))?;
+            write(&mut file)
+        }
+        None => write(out),
+    }
+}
+
+fn find_epic_of_story(db: &JiraDatabase, story_id: u32) -> Result<u32> {
+    db.read_db()?
+        .epics
+        .iter()
+        .find(|(_, epic)| epic.stories.contains(&story_id))
+        .map(|(epic_id, _)| *epic_id)
+        .ok_or_else(|| anyhow!("could not find story with id {}", story_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_utils::MockDB;
+
+    fn run_args(db: &JiraDatabase, args: &[&str]) -> Result<String> {
+        let cli = Cli::try_parse_from(std::iter::once("cli_project").chain(args.iter().copied()))?;
+        let mut out = Vec::new();
+        run(cli, db, &mut out)?;
+        Ok(String::from_utf8(out)?)
+    }
+
+    #[test]
+    fn no_subcommand_should_parse_to_none() {
+        let cli = Cli::try_parse_from(["cli_project"]).unwrap();
+        assert!(cli.command.is_none());
+        assert_eq!(cli.db_path(), "data/db.json");
+
+        let cli = Cli::try_parse_from(["cli_project", "--backend", "sqlite"]).unwrap();
+        assert_eq!(cli.db_path(), "data/db.sqlite");
+    }
+
+    #[test]
+    fn epic_create_should_print_id() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+
+        let output = run_args(
+            &db,
+            &["epic", "create", "--name", "X", "--description", "Y"],
+        )
+        .unwrap();
+        assert_eq!(output, "1\n");
+
+        let epic = db.read_db().unwrap().epics.remove(&1).unwrap();
+        assert_eq!(epic.name, "X");
+        assert_eq!(epic.description, "Y");
+    }
+
+    #[test]
+    fn story_list_should_only_list_stories_of_epic() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_1 = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let epic_2 = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        db.create_story(Story::new("first".to_owned(), "".to_owned()), epic_1)
+            .unwrap();
+        db.create_story(Story::new("second".to_owned(), "".to_owned()), epic_2)
+            .unwrap();
+
+        let output = run_args(&db, &["story", "list", "--epic", &epic_2.to_string()]).unwrap();
+        assert!(output.contains("second"));
+        assert!(!output.contains("first"));
+
+        let output = run_args(&db, &["story", "list"]).unwrap();
+        assert!(output.contains("first"));
+        assert!(output.contains("second"));
+
+        assert!(run_args(&db, &["story", "list", "--epic", "999"]).is_err());
+    }
+
+    #[test]
+    fn story_status_should_update_story() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        run_args(
+            &db,
+            &["story", "status", &story_id.to_string(), "in-progress"],
+        )
+        .unwrap();
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.stories[&story_id].status, Status::InProgress);
+
+        assert!(run_args(&db, &["story", "status", &story_id.to_string(), "done"]).is_err());
+        assert!(run_args(&db, &["story", "status", "999", "closed"]).is_err());
+    }
+
+    #[test]
+    fn story_list_should_sort_by_priority() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let epic = epic_id.to_string();
+        run_args(&db, &["story", "create", "--epic", &epic, "--name", "a"]).unwrap();
+        run_args(
+            &db,
+            &[
+                "story",
+                "create",
+                "--epic",
+                &epic,
+                "--name",
+                "b",
+                "--priority",
+                "low",
+            ],
+        )
+        .unwrap();
+        let story_id = run_args(&db, &["story", "create", "--epic", &epic, "--name", "c"]).unwrap();
+        run_args(&db, &["story", "priority", story_id.trim(), "highest"]).unwrap();
+
+        let names = |args: &[&str]| -> Vec<String> {
+            let output = run_args(&db, args).unwrap();
+            let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+            value
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|story| story["name"].as_str().unwrap().to_owned())
+                .collect()
+        };
+        assert_eq!(
+            names(&["story", "list", "--format", "json"]),
+            vec!["a", "b", "c"]
+        );
+        assert_eq!(
+            names(&["story", "list", "--sort", "priority", "--format", "json"]),
+            vec!["c", "a", "b"]
+        );
+        assert!(run_args(&db, &["story", "priority", story_id.trim(), "urgent"]).is_err());
+    }
+
+    #[test]
+    fn story_list_should_filter_by_assignee() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let epic = epic_id.to_string();
+        let story_id =
+            run_args(&db, &["story", "create", "--epic", &epic, "--name", "mine"]).unwrap();
+        run_args(
+            &db,
+            &["story", "create", "--epic", &epic, "--name", "other"],
+        )
+        .unwrap();
+
+        assert!(run_args(&db, &["story", "assign", story_id.trim(), "alice"]).is_err());
+        run_args(&db, &["user", "add", "alice", "--name", "Alice"]).unwrap();
+        run_args(&db, &["story", "assign", story_id.trim(), "alice"]).unwrap();
+        assert!(run_args(&db, &["user", "list"]).unwrap().contains("Alice"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("config.json").to_str().unwrap().to_owned();
+        std::fs::write(&config_path, r#"{ "user": "alice" }"#).unwrap();
+        for args in [
+            &["story", "list", "--assignee", "alice"][..],
+            &["story", "list", "--mine", "--config", &config_path][..],
+        ] {
+            let output = run_args(&db, args).unwrap();
+            assert!(output.contains("mine"));
+            assert!(!output.contains("other"));
+        }
+
+        assert!(run_args(&db, &["user", "remove", "alice"]).is_err());
+        run_args(&db, &["story", "unassign", story_id.trim()]).unwrap();
+        run_args(&db, &["user", "remove", "alice"]).unwrap();
+    }
+
+    #[test]
+    fn list_should_filter_by_where_expression() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        run_args(&db, &["epic", "create", "--name", "login"]).unwrap();
+        run_args(&db, &["epic", "create", "--name", "signup"]).unwrap();
+        run_args(
+            &db,
+            &["story", "create", "--epic", "2", "--name", "Login form"],
+        )
+        .unwrap();
+        run_args(
+            &db,
+            &["story", "create", "--epic", "2", "--name", "Login api"],
+        )
+        .unwrap();
+        run_args(
+            &db,
+            &["story", "create", "--epic", "1", "--name", "Login link"],
+        )
+        .unwrap();
+        run_args(&db, &["story", "status", "4", "in-progress"]).unwrap();
+        run_args(&db, &["story", "status", "5", "in-progress"]).unwrap();
+
+        let output = run_args(
+            &db,
+            &[
+                "story",
+                "list",
+                "--where",
+                r#"status:InProgress epic:2 name~"login""#,
+            ],
+        )
+        .unwrap();
+        assert!(output.contains("Login api"));
+        assert!(!output.contains("Login form"));
+        assert!(!output.contains("Login link"));
+
+        let output = run_args(&db, &["epic", "list", "--where=-name:login"]).unwrap();
+        assert!(output.contains("signup"));
+        assert!(!output.contains("login"));
+
+        let error = run_args(&db, &["story", "list", "--where", "owner:bob"]).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("field 'owner' only applies to epics at column 1"));
+    }
+
+    #[test]
+    fn list_should_filter_by_label() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic = run_args(
+            &db,
+            &["epic", "create", "--name", "ui", "--label", "frontend"],
+        )
+        .unwrap();
+        let epic = epic.trim();
+        run_args(&db, &["epic", "create", "--name", "api"]).unwrap();
+        let story_id = run_args(
+            &db,
+            &["story", "create", "--epic", epic, "--name", "button"],
+        )
+        .unwrap();
+        run_args(&db, &["story", "create", "--epic", epic, "--name", "form"]).unwrap();
+        run_args(&db, &["story", "label", story_id.trim(), "tech-debt"]).unwrap();
+
+        let output = run_args(&db, &["epic", "list", "--label", "frontend"]).unwrap();
+        assert!(output.contains("ui"));
+        assert!(!output.contains("api"));
+
+        let output = run_args(&db, &["story", "list", "--label", "tech-debt"]).unwrap();
+        assert!(output.contains("button"));
+        assert!(!output.contains("form"));
+
+        run_args(&db, &["story", "unlabel", story_id.trim(), "tech-debt"]).unwrap();
+        let output = run_args(&db, &["story", "list", "--label", "tech-debt"]).unwrap();
+        assert!(!output.contains("button"));
+
+        assert!(run_args(&db, &["epic", "create", "--name", "x", "--label", "a b"]).is_err());
+    }
+
+    #[test]
+    fn story_edit_should_keep_unspecified_fields() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(
+                Story::new("name".to_owned(), "description".to_owned()),
+                epic_id,
+            )
+            .unwrap();
+
+        run_args(
+            &db,
+            &["story", "edit", &story_id.to_string(), "--name", "new"],
+        )
+        .unwrap();
+
+        let story = db.get_story(story_id).unwrap().unwrap();
+        assert_eq!(story.name, "new");
+        assert_eq!(story.description, "description");
+        assert!(run_args(&db, &["epic", "edit", "999", "--name", "new"]).is_err());
+    }
+
+    #[test]
+    fn story_comment_should_append_comment() {
+        let mut db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        db.actor = Some("alice".to_owned());
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        run_args(&db, &["story", "comment", &story_id.to_string(), "done?"]).unwrap();
+
+        let comments = db.get_story(story_id).unwrap().unwrap().comments;
+        assert_eq!(comments.len(), 1);
+        assert_eq!(comments[0].body, "done?");
+        assert_eq!(comments[0].author.as_deref(), Some("alice"));
+        assert!(run_args(&db, &["story", "comment", "999", "done?"]).is_err());
+    }
+
+    #[test]
+    fn story_delete_should_remove_story_from_its_epic() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        run_args(&db, &["story", "delete", &story_id.to_string()]).unwrap();
+
+        let db_state = db.read_db().unwrap();
+        assert!(db_state.stories.is_empty());
+        assert!(db_state.epics[&epic_id].stories.is_empty());
+
+        assert!(run_args(&db, &["story", "delete", &story_id.to_string()]).is_err());
+    }
+
+    #[test]
+    fn story_move_should_move_story_to_target_epic() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_1 = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let epic_2 = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_1)
+            .unwrap();
+
+        run_args(
+            &db,
+            &[
+                "story",
+                "move",
+                &story_id.to_string(),
+                "--to",
+                &epic_2.to_string(),
+            ],
+        )
+        .unwrap();
+
+        let db_state = db.read_db().unwrap();
+        assert!(db_state.epics[&epic_1].stories.is_empty());
+        assert_eq!(db_state.epics[&epic_2].stories, vec![story_id]);
+
+        assert!(run_args(
+            &db,
+            &["story", "move", &story_id.to_string(), "--to", "999"]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn story_list_should_support_json_format() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("name".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let output = run_args(&db, &["story", "list", "--format", "json"]).unwrap();
+
+        let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(value[0]["id"], story_id);
+        assert_eq!(value[0]["epic_id"], epic_id);
+        assert_eq!(value[0]["name"], "name");
+        assert_eq!(value[0]["status"], "Open");
+    }
+
+    #[test]
+    fn migrate_should_refuse_to_overwrite_without_force() {
+        let dir = tempfile::tempdir().unwrap();
+        let json_path = dir.path().join("db.json").to_str().unwrap().to_owned();
+        let sqlite_path = dir.path().join("db.sqlite").to_str().unwrap().to_owned();
+        let json = JiraDatabase::new(json_path.clone(), DEFAULT_LOCK_TIMEOUT);
+        json.database
+            .write_db(&crate::models::DBState {
+                last_item_id: 0,
+                epics: std::collections::HashMap::new(),
+                stories: std::collections::HashMap::new(),
+                users: vec![],
+            })
+            .unwrap();
+        json.create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let args = ["migrate", "--from", &json_path, "--to", &sqlite_path];
+
+        let output = run_args(&json, &args).unwrap();
+        assert_eq!(
+            output,
+            format!("migrated 1 epics and 0 stories to {}\n", sqlite_path)
+        );
+
+        assert!(run_args(&json, &args).is_err());
+        assert!(run_args(&json, &[&args[..], &["--force"]].concat()).is_ok());
+    }
+
+    #[test]
+    fn export_csv_should_write_file_that_import_reads() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        db.create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let csv_path = dir.path().join("backlog.csv").to_str().unwrap().to_owned();
+
+        let output = run_args(&db, &["export", "csv", "--output", &csv_path]).unwrap();
+        assert_eq!(output, "");
+
+        let other = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let output = run_args(&other, &["import", "csv", &csv_path]).unwrap();
+        assert_eq!(output, "imported 1 epics and 1 stories\n");
+
+        std::fs::write(&csv_path, "epic_name,name,status\nepic,story,done\n").unwrap();
+        assert!(run_args(&other, &["import", "csv", &csv_path]).is_err());
+        assert_eq!(other.list_stories().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn import_jira_should_use_configured_statuses() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let dir = tempfile::tempdir().unwrap();
+        let export_path = dir.path().join("jira.json").to_str().unwrap().to_owned();
+        let config_path = dir.path().join("config.json").to_str().unwrap().to_owned();
+        std::fs::write(
+            &export_path,
+            r#"{"issues": [
+                {"key": "WEB-1", "fields": {"summary": "Login", "issuetype": {"name": "Epic"},
+                    "status": {"name": "Waiting"}}},
+                {"key": "WEB-2", "fields": {"summary": "Form", "issuetype": {"name": "Story"},
+                    "status": {"name": "Done"}, "parent": {"key": "WEB-1"}}}
+            ]}"#,
+        )
+        .unwrap();
+        let args = ["import", "jira", &export_path, "--config", &config_path];
+
+        assert!(run_args(&db, &args).is_err());
+        assert_eq!(db.list_stories().unwrap().len(), 0);
+
+        std::fs::write(
+            &config_path,
+            r#"{"jira_statuses": {"WAITING": "InProgress"}}"#,
+        )
+        .unwrap();
+        let output = run_args(&db, &args).unwrap();
+        assert_eq!(output, "imported 1 epics and 1 stories\n");
+        let epic = db.list_epics().unwrap().into_values().next().unwrap();
+        assert_eq!(epic.status, Status::InProgress);
+        assert_eq!(epic.jira_key.as_deref(), Some("WEB-1"));
+    }
+
+    #[test]
+    fn report_markdown_should_filter_stories_by_status() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("done".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        db.create_story(Story::new("todo".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        db.update_story_status(story_id, Status::Closed).unwrap();
+
+        let output = run_args(&db, &["report", "markdown", "--status", "closed"]).unwrap();
+        assert_eq!(
+            output,
+            format!(
+                "## epic (#{})\n\nStatus: Open\n\n- [x] done (#{}, Closed)\n",
+                epic_id, story_id
+            )
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let report_path = dir.path().join("report.md").to_str().unwrap().to_owned();
+        let output = run_args(&db, &["report", "markdown", "-o", &report_path]).unwrap();
+        assert_eq!(output, "");
+        assert!(std::fs::read_to_string(&report_path)
+            .unwrap()
+            .contains("- [ ] todo"));
+    }
+
+    #[test]
+    fn undo_and_redo_should_report_what_they_did() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        assert_eq!(run_args(&db, &["undo"]).unwrap(), "nothing to undo\n");
+
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+
+        assert_eq!(
+            run_args(&db, &["undo"]).unwrap(),
+            format!("undid create epic {}\n", epic_id)
+        );
+        assert!(db.list_epics().unwrap().is_empty());
+        assert_eq!(
+            run_args(&db, &["redo"]).unwrap(),
+            format!("redid create epic {}\n", epic_id)
+        );
+        assert_eq!(run_args(&db, &["redo"]).unwrap(), "nothing to redo\n");
+        assert_eq!(db.list_epics().unwrap().len(), 1);
+    }
+}
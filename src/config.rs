@@ -0,0 +1,96 @@
+use std::{collections::HashMap, env, fs::read_to_string, path::Path};
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+
+use crate::models::Status;
+use crate::workflow::Workflow;
+
+pub const DEFAULT_CONFIG_PATH: &str = "data/config.json";
+
+/// Local settings of the user, read from a JSON file that may be missing.
+#[derive(Deserialize, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct Config {
+    /// Username of the current user, overrides `$USER`.
+    pub user: Option<String>,
+    /// Statuses and allowed status changes, any change is allowed by default.
+    pub workflow: Workflow,
+    /// Jira status names mapped to statuses for `import jira`, on top of the built-in mapping.
+    pub jira_statuses: HashMap<String, Status>,
+}
+
+impl Config {
+    pub fn load(file_path: &str) -> Result<Self> {
+        if !Path::new(file_path).exists() {
+            return Ok(Self::default());
+        }
+        let serialized = read_to_string(file_path)?;
+        let config: Self = serde_json::from_str(&serialized)
+            .with_context(|| anyhow!("failed to parse config file {}", file_path))?;
+        config
+            .workflow
+            .validate()
+            .map_err(|error| anyhow!("invalid config file {}: {}", file_path, error))?;
+        Ok(config)
+    }
+    /// The configured user, else the login name from `$USER` (`$USERNAME` on Windows).
+    pub fn current_user(&self) -> Option<String> {
+        self.user
+            .clone()
+            .or_else(|| env::var("USER").ok())
+            .or_else(|| env::var("USERNAME").ok())
+            .filter(|user| !user.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_should_default_for_missing_file() {
+        assert_eq!(
+            Config::load("missing/config.json").unwrap(),
+            Config::default()
+        );
+    }
+
+    #[test]
+    fn configured_user_should_be_current_user() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("config.json").to_str().unwrap().to_owned();
+        std::fs::write(&file_path, r#"{ "user": "alice" }"#).unwrap();
+
+        let config = Config::load(&file_path).unwrap();
+        assert_eq!(config.current_user().as_deref(), Some("alice"));
+
+        std::fs::write(&file_path, r#"{ "user": 1 }"#).unwrap();
+        assert!(Config::load(&file_path).is_err());
+    }
+
+    #[test]
+    fn workflow_should_be_read_and_validated() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("config.json").to_str().unwrap().to_owned();
+        std::fs::write(
+            &file_path,
+            r#"{ "workflow": { "statuses": ["Open", "InProgress", "Closed"], "transitions": { "Closed": [] } } }"#,
+        )
+        .unwrap();
+
+        let workflow = Config::load(&file_path).unwrap().workflow;
+        assert_eq!(
+            workflow.statuses,
+            vec![Status::Open, Status::InProgress, Status::Closed]
+        );
+        assert!(workflow.next_statuses(&Status::Closed).is_empty());
+
+        std::fs::write(
+            &file_path,
+            r#"{ "workflow": { "statuses": ["InProgress", "Closed"] } }"#,
+        )
+        .unwrap();
+        assert!(Config::load(&file_path).is_err());
+    }
+}
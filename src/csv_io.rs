@@ -0,0 +1,311 @@
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+};
+
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::db::JiraDatabase;
+use crate::models::{validate_label, DBState, Epic, Priority, Status, Story, User};
+
+/// One line of a CSV file: a story with its epic, or an epic alone when the story columns are empty.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(default)]
+struct CsvRow {
+    epic_id: Option<u32>,
+    epic_name: String,
+    story_id: Option<u32>,
+    name: String,
+    description: String,
+    status: String,
+    priority: String,
+    assignee: String,
+    /// Labels separated by spaces.
+    labels: String,
+}
+
+/// Outcome of a CSV import, rows that failed are reported with their line number.
+#[derive(Debug, Default, PartialEq)]
+pub struct ImportReport {
+    pub epics: usize,
+    pub stories: usize,
+    pub errors: Vec<(u64, String)>,
+}
+
+/// Writes one row per story with its epic, epics without stories get a row of their own.
+pub fn export_csv(db_state: &DBState, out: &mut dyn Write) -> Result<()> {
+    let mut writer = csv::Writer::from_writer(out);
+    let mut epic_ids: Vec<&u32> = db_state.epics.keys().collect();
+    epic_ids.sort();
+    for epic_id in epic_ids {
+        let epic = &db_state.epics[epic_id];
+        let stories: Vec<(&u32, &Story)> = epic
+            .stories
+            .iter()
+            .filter_map(|story_id| Some((story_id, db_state.stories.get(story_id)?)))
+            .collect();
+        if stories.is_empty() {
+            writer.serialize(CsvRow {
+                epic_id: Some(*epic_id),
+                epic_name: epic.name.clone(),
+                ..CsvRow::default()
+            })?;
+        }
+        for (story_id, story) in stories {
+            writer.serialize(CsvRow {
+                epic_id: Some(*epic_id),
+                epic_name: epic.name.clone(),
+                story_id: Some(*story_id),
+                name: story.name.clone(),
+                description: story.description.clone(),
+                status: format!("{:?}", story.status),
+                priority: format!("{:?}", story.priority),
+                assignee: story.assignee.clone().unwrap_or_default(),
+                labels: story.labels.iter().cloned().collect::<Vec<_>>().join(" "),
+            })?;
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Creates the epics and stories of a CSV file through `JiraDatabase`, skipping rows with errors.
+///
+/// Epics are matched by name: rows go into the existing epic of that name, or into one created
+/// for the first row naming it. Ids in the file are ignored since the database assigns new ones.
+/// The whole import is undone at once.
+pub fn import_csv(db: &JiraDatabase, input: &mut dyn Read) -> Result<ImportReport> {
+    db.batch("import csv".to_owned(), || import_rows(db, input))
+}
+
+fn import_rows(db: &JiraDatabase, input: &mut dyn Read) -> Result<ImportReport> {
+    let mut reader = csv::Reader::from_reader(input);
+    let headers = reader.headers()?.clone();
+    let users = db.list_users()?;
+    let mut epic_ids: HashMap<String, Vec<u32>> = HashMap::new();
+    for (id, epic) in db.list_epics()? {
+        epic_ids.entry(epic.name).or_default().push(id);
+    }
+    let mut report = ImportReport::default();
+    for record in reader.records() {
+        let record = match record {
+            Ok(record) => record,
+            Err(error) => {
+                let line = error.position().map_or(0, |position| position.line());
+                report.errors.push((line, error.to_string()));
+                continue;
+            }
+        };
+        let line = record.position().map_or(0, |position| position.line());
+        let row = record
+            .deserialize::<CsvRow>(Some(&headers))
+            .map_err(|error| error.to_string())
+            .and_then(|row| {
+                parse_story(&row, &users, &db.workflow.statuses).map(|story| (row, story))
+            });
+        let (row, story) = match row {
+            Ok(row) => row,
+            Err(error) => {
+                report.errors.push((line, error));
+                continue;
+            }
+        };
+        if let Err(error) = import_row(db, &mut epic_ids, &row.epic_name, story, &mut report) {
+            report.errors.push((line, format!("{:#}", error)));
+        }
+    }
+    Ok(report)
+}
+
+/// Adds the story of a row to the epic of that name, creating the epic if there is none yet.
+/// Names shared by several epics are rejected since the row could belong to any of them.
+fn import_row(
+    db: &JiraDatabase,
+    epic_ids: &mut HashMap<String, Vec<u32>>,
+    epic_name: &str,
+    story: Option<Story>,
+    report: &mut ImportReport,
+) -> Result<()> {
+    let epic_id = match epic_ids.get(epic_name).map(Vec::as_slice) {
+        Some([epic_id]) => *epic_id,
+        Some(ids) if !ids.is_empty() => {
+            return Err(anyhow!(
+                "epic name '{}' is ambiguous (epics {})",
+                epic_name,
+                ids.iter().sorted().join(", ")
+            ));
+        }
+        _ => {
+            let epic_id = db.create_epic(Epic::new(epic_name.to_owned(), "".to_owned()))?;
+            epic_ids.insert(epic_name.to_owned(), vec![epic_id]);
+            report.epics += 1;
+            epic_id
+        }
+    };
+    if let Some(story) = story {
+        db.create_story(story, epic_id)?;
+        report.stories += 1;
+    }
+    Ok(())
+}
+
+/// The story of a row, `None` for a row that only names an epic.
+fn parse_story(row: &CsvRow, users: &[User], statuses: &[Status]) -> Result<Option<Story>, String> {
+    if row.epic_name.trim().is_empty() {
+        return Err("missing epic_name".to_owned());
+    }
+    if row.name.is_empty() {
+        return Ok(None);
+    }
+    let mut story = Story::new(row.name.clone(), row.description.clone());
+    if !row.status.is_empty() {
+        story.status = row.status.parse::<Status>()?;
+        if !statuses.contains(&story.status) {
+            return Err(format!("status {:?} is not in the workflow", story.status));
+        }
+    }
+    if !row.priority.is_empty() {
+        story.priority = row.priority.parse::<Priority>()?;
+    }
+    if !row.assignee.is_empty() {
+        if !users.iter().any(|user| user.username == row.assignee) {
+            return Err(format!("unknown user '{}'", row.assignee));
+        }
+        story.assignee = Some(row.assignee.clone());
+    }
+    for label in row.labels.split_whitespace() {
+        validate_label(label)?;
+        story.labels.insert(label.to_owned());
+    }
+    Ok(Some(story))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_utils::MockDB;
+    use crate::workflow::Workflow;
+
+    #[test]
+    fn export_should_write_one_row_per_story() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("Login".to_owned(), "".to_owned()))
+            .unwrap();
+        let mut story = Story::new("Form, with \"quotes\"".to_owned(), "two\nlines".to_owned());
+        story.labels.insert("ui".to_owned());
+        story.labels.insert("bug".to_owned());
+        let story_id = db.create_story(story, epic_id).unwrap();
+        db.update_story_status(story_id, Status::InProgress)
+            .unwrap();
+        db.create_epic(Epic::new("Empty".to_owned(), "".to_owned()))
+            .unwrap();
+
+        let mut out = vec![];
+        export_csv(&db.read_db().unwrap(), &mut out).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "epic_id,epic_name,story_id,name,description,status,priority,assignee,labels\n\
+             1,Login,2,\"Form, with \"\"quotes\"\"\",\"two\nlines\",InProgress,Medium,,bug ui\n\
+             3,Empty,,,,,,,\n"
+        );
+    }
+
+    #[test]
+    fn import_should_create_items_and_report_failed_rows() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        db.add_user(User::new("alice".to_owned(), "".to_owned()))
+            .unwrap();
+        let existing_id = db
+            .create_epic(Epic::new("Login".to_owned(), "".to_owned()))
+            .unwrap();
+        let input = "epic_name,name,status,priority,assignee,labels\n\
+                     Login,Form,in-progress,high,alice,ui bug\n\
+                     Signup,Captcha,,,,\n\
+                     Signup,Mail,done,,,\n\
+                     ,Orphan,,,,\n\
+                     Signup,Welcome,,,bob,\n\
+                     Billing,,,,,\n";
+
+        let report = import_csv(&db, &mut input.as_bytes()).unwrap();
+
+        assert_eq!(report.epics, 2);
+        assert_eq!(report.stories, 2);
+        assert_eq!(report.errors.len(), 3);
+        assert_eq!(report.errors[0].0, 4);
+        assert!(report.errors[0].1.starts_with("invalid status 'done'"));
+        assert_eq!(report.errors[1], (5, "missing epic_name".to_owned()));
+        assert_eq!(report.errors[2], (6, "unknown user 'bob'".to_owned()));
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.epics.len(), 3);
+        let login = &db_state.epics[&existing_id];
+        let form = &db_state.stories[&login.stories[0]];
+        assert_eq!(form.status, Status::InProgress);
+        assert_eq!(form.priority, Priority::High);
+        assert_eq!(form.assignee.as_deref(), Some("alice"));
+        assert_eq!(form.labels.len(), 2);
+
+        assert_eq!(db.undo().unwrap(), Some("import csv".to_owned()));
+        assert_eq!(db.list_epics().unwrap().len(), 1);
+        assert!(db.list_stories().unwrap().is_empty());
+    }
+
+    #[test]
+    fn import_should_reject_statuses_outside_the_workflow_and_ambiguous_epics() {
+        let mut db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        db.workflow = Workflow {
+            statuses: vec![Status::Open, Status::Closed],
+            ..Workflow::default()
+        };
+        for _ in 0..2 {
+            db.create_epic(Epic::new("Login".to_owned(), "".to_owned()))
+                .unwrap();
+        }
+        let input = "epic_name,name,status\n\
+                     Signup,Captcha,closed\n\
+                     Signup,Mail,in-progress\n\
+                     Login,Form,\n";
+
+        let report = import_csv(&db, &mut input.as_bytes()).unwrap();
+
+        assert_eq!(report.epics, 1);
+        assert_eq!(report.stories, 1);
+        assert_eq!(
+            report.errors,
+            vec![
+                (3, "status InProgress is not in the workflow".to_owned()),
+                (4, "epic name 'Login' is ambiguous (epics 1, 2)".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn import_should_read_exported_files() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("Login".to_owned(), "".to_owned()))
+            .unwrap();
+        db.create_story(Story::new("Form".to_owned(), "a\nb".to_owned()), epic_id)
+            .unwrap();
+        let mut out = vec![];
+        export_csv(&db.read_db().unwrap(), &mut out).unwrap();
+
+        let other = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let report = import_csv(&other, &mut out.as_slice()).unwrap();
+
+        assert_eq!(
+            report,
+            ImportReport {
+                epics: 1,
+                stories: 1,
+                errors: vec![],
+            }
+        );
+        let story = other.list_stories().unwrap().into_values().next().unwrap();
+        assert_eq!(story.description, "a\nb");
+    }
+}
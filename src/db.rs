@@ -1,10 +1,24 @@
-use crate::models::{DBState, Epic, Status, Story};
-use anyhow::{anyhow, Result};
+use crate::models::{
+    validate_label, Change, Comment, DBState, Epic, FieldChange, History, ItemChange, Priority,
+    Status, Story, User,
+};
+use crate::workflow::{rollup_status, Workflow};
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
 use std::{
-    collections::HashMap,
-    fs::{read_to_string, File},
+    cell::{Cell, RefCell},
+    collections::{BTreeSet, HashMap},
+    fs::{read_to_string, remove_file, rename, File, OpenOptions, TryLockError},
     io::Write,
+    path::Path,
+    process,
+    thread::sleep,
+    time::{Duration, Instant},
 };
+pub mod migrations;
+pub mod sqlite;
 fn add_key_value<T>(mut map: HashMap<u32, T>, key: u32, value: T) -> HashMap<u32, T> {
     map.insert(key, value);
     map
@@ -13,56 +27,83 @@ fn remove_key_value<T>(mut map: HashMap<u32, T>, key: &u32) -> HashMap<u32, T> {
     map.remove(key);
     map
 }
+/// Storage backend of the tracker.
+///
+/// Only `read_db` and `write_db` are required, the item-level operations default to a
+/// whole-state read-modify-write and can be overridden by backends that can do better.
 pub trait Database {
     fn read_db(&self) -> Result<DBState>;
     fn write_db(&self, db_state: &DBState) -> Result<()>;
-}
-pub struct JSONFileDatabase {
-    pub file_path: String,
-}
-impl Database for JSONFileDatabase {
-    fn read_db(&self) -> Result<DBState> {
-        let serialized = read_to_string(&self.file_path)?;
-        let deserialized: DBState = serde_json::from_str(&serialized)?;
-        Ok(deserialized)
+    /// Takes an exclusive lock for a read-modify-write cycle, released when the guard is dropped.
+    fn lock(&self) -> Result<DatabaseLock> {
+        Ok(DatabaseLock { _file: None })
     }
-    fn write_db(&self, db_state: &DBState) -> Result<()> {
-        let serialized = serde_json::to_string(&db_state)?;
-        let mut file = File::create(&self.file_path)?;
-        file.write_all(serialized.as_bytes())?;
+    /// Undo/redo history of the database, backends without storage for it keep none.
+    fn read_history(&self) -> Result<History> {
+        Ok(History::default())
+    }
+    fn write_history(&self, _history: &History) -> Result<()> {
         Ok(())
     }
-}
-pub struct JiraDatabase {
-    pub database: Box<dyn Database>,
-}
-impl JiraDatabase {
-    pub fn new(file_path: String) -> Self {
-        Self {
-            database: Box::new(JSONFileDatabase { file_path }),
+    /// Append-only log of field changes, backends without storage for it keep none.
+    fn read_changelog(&self, _item_id: u32) -> Result<Vec<FieldChange>> {
+        Ok(vec![])
+    }
+    fn append_changelog(&self, _entries: &[FieldChange]) -> Result<()> {
+        Ok(())
+    }
+
+    fn get_epic(&self, epic_id: u32) -> Result<Option<Epic>> {
+        Ok(self.read_db()?.epics.remove(&epic_id))
+    }
+    fn get_story(&self, story_id: u32) -> Result<Option<Story>> {
+        Ok(self.read_db()?.stories.remove(&story_id))
+    }
+    fn list_epics(&self) -> Result<HashMap<u32, Epic>> {
+        Ok(self.read_db()?.epics)
+    }
+    fn list_stories(&self) -> Result<HashMap<u32, Story>> {
+        Ok(self.read_db()?.stories)
+    }
+    fn list_users(&self) -> Result<Vec<User>> {
+        Ok(self.read_db()?.users)
+    }
+    fn insert_user(&self, user: User) -> Result<()> {
+        let mut db = self.read_db()?;
+        if db.users.iter().any(|u| u.username == user.username) {
+            return Err(anyhow!("user {} already exists", user.username));
         }
+        db.users.push(user);
+        self.write_db(&db)
     }
-    pub fn read_db(&self) -> Result<DBState> {
-        self.database.read_db()
+    fn delete_user(&self, username: &str) -> Result<()> {
+        let mut db = self.read_db()?;
+        let count = db.users.len();
+        db.users.retain(|user| user.username != username);
+        if db.users.len() == count {
+            return Err(anyhow!("could not find user {} in database", username));
+        }
+        self.write_db(&db)
     }
 
-    pub fn create_epic(&self, epic: Epic) -> Result<u32> {
+    fn insert_epic(&self, epic: Epic) -> Result<u32> {
         let db_old = self.read_db()?;
         let db_new = DBState {
             last_item_id: db_old.last_item_id + 1,
             epics: add_key_value(db_old.epics, db_old.last_item_id + 1, epic), //generic known throu type inference
             stories: db_old.stories,
+            users: db_old.users,
         };
-        self.database.write_db(&db_new)?;
+        self.write_db(&db_new)?;
         Ok(db_new.last_item_id)
     }
-
-    pub fn create_story(&self, story: Story, epic_id: u32) -> Result<u32> {
+    fn insert_story(&self, story: Story, epic_id: u32) -> Result<u32> {
         let db_old = self.read_db()?;
         let mut db_new = DBState {
             last_item_id: db_old.last_item_id + 1,
             epics: db_old.epics,
             stories: add_key_value(db_old.stories, db_old.last_item_id + 1, story),
+            users: db_old.users,
         };
         db_new
             .epics
@@ -70,11 +111,58 @@ impl JiraDatabase {
             .ok_or_else(|| anyhow!("could not find epic in database for create story"))?
             .stories
             .push(db_new.last_item_id);
-        self.database.write_db(&db_new)?;
+        self.write_db(&db_new)?;
         Ok(db_new.last_item_id)
     }
 
-    pub fn delete_epic(&self, epic_id: u32) -> Result<()> {
+    fn update_epic(&self, epic_id: u32, epic: Epic) -> Result<()> {
+        let mut db = self.read_db()?;
+        *db.epics
+            .get_mut(&epic_id)
+            .ok_or_else(|| anyhow!("could not find epic {} in database for update", epic_id))? =
+            epic;
+        self.write_db(&db)
+    }
+    fn update_story(&self, story_id: u32, story: Story) -> Result<()> {
+        let mut db = self.read_db()?;
+        *db.stories
+            .get_mut(&story_id)
+            .ok_or_else(|| anyhow!("could not find story {} in database for update", story_id))? =
+            story;
+        self.write_db(&db)
+    }
+
+    fn move_story(&self, story_id: u32, from_epic_id: u32, to_epic_id: u32) -> Result<()> {
+        let mut db = self.read_db()?;
+        if from_epic_id == to_epic_id {
+            return Err(anyhow!(
+                "story {} is already in epic {}",
+                story_id,
+                to_epic_id
+            ));
+        }
+        if !db.epics.contains_key(&to_epic_id) {
+            return Err(anyhow!("could not find epic in database for move story"));
+        }
+        let from_epic = db
+            .epics
+            .get_mut(&from_epic_id)
+            .ok_or_else(|| anyhow!("could not find epic in database for move story"))?;
+        if !from_epic.stories.contains(&story_id) {
+            return Err(anyhow!(
+                "could not find story_id in epic stories for move story"
+            ));
+        }
+        from_epic.stories.retain(|id| *id != story_id);
+        db.epics
+            .get_mut(&to_epic_id)
+            .unwrap()
+            .stories
+            .push(story_id);
+        self.write_db(&db)
+    }
+
+    fn delete_epic(&self, epic_id: u32) -> Result<()> {
         let mut db_old = self.read_db()?;
         for story_id in &db_old
             .epics
@@ -85,20 +173,20 @@ impl JiraDatabase {
             db_old.stories.remove(story_id);
         }
         let db_new = DBState {
-            last_item_id: db_old.last_item_id, 
+            last_item_id: db_old.last_item_id,
             epics: remove_key_value(db_old.epics, &epic_id),
             stories: db_old.stories,
+            users: db_old.users,
         };
-        self.database.write_db(&db_new)?;
-        Ok(())
+        self.write_db(&db_new)
     }
-
-    pub fn delete_story(&self, epic_id: u32, story_id: u32) -> Result<()> {
+    fn delete_story(&self, epic_id: u32, story_id: u32) -> Result<()> {
         let db_old = self.read_db()?;
         let mut db_new = DBState {
-            last_item_id: db_old.last_item_id, 
+            last_item_id: db_old.last_item_id,
             epics: db_old.epics,
             stories: remove_key_value(db_old.stories, &story_id),
+            users: db_old.users,
         };
         db_new
             .epics
@@ -114,29 +202,792 @@ impl JiraDatabase {
             .unwrap()
             .stories
             .retain(|x| *x != story_id); //todo!() not really clean how to do it in one combinator?
-        self.database.write_db(&db_new)?;
+        self.write_db(&db_new)
+    }
+}
+pub struct DatabaseLock {
+    _file: Option<File>,
+}
+pub const DEFAULT_LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+pub struct JSONFileDatabase {
+    pub file_path: String,
+    pub lock_timeout: Duration,
+    /// Schema version of the file at the last `read_db`, `None` before the first read.
+    loaded_version: Cell<Option<u64>>,
+}
+impl JSONFileDatabase {
+    pub fn new(file_path: String) -> Self {
+        Self {
+            file_path,
+            lock_timeout: DEFAULT_LOCK_TIMEOUT,
+            loaded_version: Cell::new(None),
+        }
+    }
+}
+/// On-disk layout of the JSON database, the state tagged with its schema version.
+#[derive(Serialize)]
+struct VersionedDBState<'a> {
+    schema_version: u64,
+    #[serde(flatten)]
+    state: &'a DBState,
+}
+impl Database for JSONFileDatabase {
+    /// Upgrades files of older schema versions in memory, after keeping a copy of the file as
+    /// `<file_path>.v<version>.bak`. The upgrade is persisted by the next `write_db`, which runs
+    /// under the lock.
+    fn read_db(&self) -> Result<DBState> {
+        let serialized = read_to_string(&self.file_path)?;
+        let mut value: serde_json::Value = serde_json::from_str(&serialized)?;
+        let version = migrations::migrate(&mut value)?;
+        if version < migrations::CURRENT_SCHEMA_VERSION
+            && self.loaded_version.get() != Some(version)
+        {
+            let backup_path = format!("{}.v{}.bak", self.file_path, version);
+            write_file_atomically(&backup_path, &serialized)
+                .with_context(|| anyhow!("failed to back up database to {}", backup_path))?;
+        }
+        self.loaded_version.set(Some(version));
+        Ok(serde_json::from_value(value)?)
+    }
+    fn write_db(&self, db_state: &DBState) -> Result<()> {
+        let serialized = serde_json::to_string(&VersionedDBState {
+            schema_version: migrations::CURRENT_SCHEMA_VERSION,
+            state: db_state,
+        })?;
+        write_file_atomically(&self.file_path, &serialized)?;
+        self.loaded_version
+            .set(Some(migrations::CURRENT_SCHEMA_VERSION));
         Ok(())
     }
+    fn lock(&self) -> Result<DatabaseLock> {
+        lock_file(&self.file_path, self.lock_timeout)
+    }
+    fn read_history(&self) -> Result<History> {
+        let history_path = format!("{}.history", self.file_path);
+        if !Path::new(&history_path).exists() {
+            return Ok(History::default());
+        }
+        Ok(serde_json::from_str(&read_to_string(&history_path)?)?)
+    }
+    fn write_history(&self, history: &History) -> Result<()> {
+        let serialized = serde_json::to_string(history)?;
+        write_file_atomically(&format!("{}.history", self.file_path), &serialized)
+    }
+    fn read_changelog(&self, item_id: u32) -> Result<Vec<FieldChange>> {
+        let changelog_path = format!("{}.changelog", self.file_path);
+        if !Path::new(&changelog_path).exists() {
+            return Ok(vec![]);
+        }
+        let mut entries = vec![];
+        for line in read_to_string(&changelog_path)?.lines() {
+            let entry: FieldChange = serde_json::from_str(line)
+                .with_context(|| anyhow!("failed to parse change log {}", changelog_path))?;
+            if entry.item_id == item_id {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+    fn append_changelog(&self, entries: &[FieldChange]) -> Result<()> {
+        // one JSON object per line, so appending never rewrites earlier entries
+        let mut serialized = String::new();
+        for entry in entries {
+            serialized += &serde_json::to_string(entry)?;
+            serialized.push('\n');
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(format!("{}.changelog", self.file_path))?;
+        file.write_all(serialized.as_bytes())?;
+        file.sync_all()?;
+        Ok(())
+    }
+}
+fn write_file_atomically(file_path: &str, contents: &str) -> Result<()> {
+    // write to a temp file next to the target and rename it over the old file,
+    // so a crash or full disk mid-write never leaves a truncated file behind
+    let temp_path = format!("{}.tmp", file_path);
+    let write_temp = || -> Result<()> {
+        let mut file = File::create(&temp_path)?;
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()?;
+        Ok(())
+    };
+    if let Err(error) = write_temp().and_then(|_| Ok(rename(&temp_path, file_path)?)) {
+        let _ = remove_file(&temp_path);
+        return Err(error);
+    }
+    sync_parent_dir(Path::new(file_path))?;
+    Ok(())
+}
+/// Advisory lock on a `<file_path>.lock` sidecar file that also records the pid of the holder.
+pub fn lock_file(file_path: &str, timeout: Duration) -> Result<DatabaseLock> {
+    let lock_path = format!("{}.lock", file_path);
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(&lock_path)?;
+    let started = Instant::now();
+    loop {
+        match file.try_lock() {
+            Ok(()) => break,
+            Err(TryLockError::WouldBlock) => {
+                if started.elapsed() >= timeout {
+                    let holder = read_to_string(&lock_path).unwrap_or_default();
+                    return Err(match holder.trim().parse::<u32>() {
+                        Ok(pid) => anyhow!("database is locked by pid {}", pid),
+                        Err(_) => anyhow!("database is locked by another process"),
+                    });
+                }
+                sleep(Duration::from_millis(50));
+            }
+            Err(TryLockError::Error(error)) => return Err(error.into()),
+        }
+    }
+    file.set_len(0)?;
+    write!(file, "{}", process::id())?;
+    Ok(DatabaseLock { _file: Some(file) })
+}
+/// Copies the whole state of one database into another, with its undo history and change log,
+/// and verifies that nothing was lost. Change log entries the target already has are kept once.
+pub fn copy_database(from: &dyn Database, to: &dyn Database) -> Result<DBState> {
+    let _from_lock = from.lock()?;
+    let _to_lock = to.lock()?;
+    let db_state = from.read_db()?;
+    to.write_db(&db_state)?;
+    if to.read_db()? != db_state {
+        return Err(anyhow!("copied database does not match the original"));
+    }
+    let history = from.read_history()?;
+    to.write_history(&history)?;
+    if to.read_history()? != history {
+        return Err(anyhow!("copied history does not match the original"));
+    }
+    // ids are never reused, so this covers the log of deleted items as well
+    for item_id in 1..=db_state.last_item_id {
+        let entries = from.read_changelog(item_id)?;
+        let existing = to.read_changelog(item_id)?;
+        let missing: Vec<FieldChange> = entries
+            .iter()
+            .filter(|entry| !existing.contains(entry))
+            .cloned()
+            .collect();
+        to.append_changelog(&missing)?;
+        let copied = to.read_changelog(item_id)?;
+        if entries.iter().any(|entry| !copied.contains(entry)) {
+            return Err(anyhow!(
+                "copied change log of item {} does not match the original",
+                item_id
+            ));
+        }
+    }
+    Ok(db_state)
+}
+#[cfg(unix)]
+fn sync_parent_dir(path: &Path) -> Result<()> {
+    // the rename is only durable once the directory entry itself is flushed
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    File::open(dir)?.sync_all()?;
+    Ok(())
+}
+#[cfg(not(unix))]
+fn sync_parent_dir(_path: &Path) -> Result<()> {
+    Ok(())
+}
+pub struct JiraDatabase {
+    pub database: Box<dyn Database>,
+    /// Username recorded in the change log for every change, `None` if unknown.
+    pub actor: Option<String>,
+    /// Status changes that `update_epic_status` and `update_story_status` allow.
+    pub workflow: Workflow,
+    /// Collects the changes of a running `batch`, `None` outside of one.
+    batch: RefCell<Option<Change>>,
+}
+impl JiraDatabase {
+    pub fn new(file_path: String, lock_timeout: Duration) -> Self {
+        let mut database = JSONFileDatabase::new(file_path);
+        database.lock_timeout = lock_timeout;
+        Self::with_database(Box::new(database))
+    }
+    /// Tracker on any backend without an actor and with the default workflow.
+    pub fn with_database(database: Box<dyn Database>) -> Self {
+        Self {
+            database,
+            actor: None,
+            workflow: Workflow::default(),
+            batch: RefCell::new(None),
+        }
+    }
+    pub fn read_db(&self) -> Result<DBState> {
+        self.database.read_db()
+    }
+
+    pub fn get_epic(&self, epic_id: u32) -> Result<Option<Epic>> {
+        self.database.get_epic(epic_id)
+    }
+    pub fn get_story(&self, story_id: u32) -> Result<Option<Story>> {
+        self.database.get_story(story_id)
+    }
+    pub fn list_epics(&self) -> Result<HashMap<u32, Epic>> {
+        self.database.list_epics()
+    }
+    pub fn list_stories(&self) -> Result<HashMap<u32, Story>> {
+        self.database.list_stories()
+    }
+    pub fn list_users(&self) -> Result<Vec<User>> {
+        self.database.list_users()
+    }
+    /// Changes of the fields of an epic or story, oldest first.
+    pub fn get_item_history(&self, item_id: u32) -> Result<Vec<FieldChange>> {
+        self.database.read_changelog(item_id)
+    }
+
+    /// Stories of an epic that are neither resolved nor closed.
+    pub fn open_stories(&self, epic_id: u32) -> Result<Vec<u32>> {
+        let epic = self
+            .database
+            .get_epic(epic_id)?
+            .ok_or_else(|| anyhow!("could not find epic with id {}", epic_id))?;
+        let mut open = vec![];
+        for story_id in epic.stories {
+            if let Some(story) = self.database.get_story(story_id)? {
+                if matches!(story.status, Status::Open | Status::InProgress) {
+                    open.push(story_id);
+                }
+            }
+        }
+        Ok(open)
+    }
+
+    /// Registers a new user, changes to the registry are not part of the undo history.
+    pub fn add_user(&self, user: User) -> Result<()> {
+        let _lock = self.lock()?;
+        self.database.insert_user(user)
+    }
+
+    /// Removes a user from the registry, fails while epics or stories are still assigned to them.
+    pub fn remove_user(&self, username: &str) -> Result<()> {
+        let _lock = self.lock()?;
+        let db = self.database.read_db()?;
+        if let Some(epic_id) = db
+            .epics
+            .iter()
+            .find(|(_, epic)| epic.owner.as_deref() == Some(username))
+            .map(|(id, _)| id)
+        {
+            return Err(anyhow!("user {} still owns epic {}", username, epic_id));
+        }
+        if let Some(story_id) = db
+            .stories
+            .iter()
+            .find(|(_, story)| story.assignee.as_deref() == Some(username))
+            .map(|(id, _)| id)
+        {
+            return Err(anyhow!(
+                "user {} is still assigned to story {}",
+                username,
+                story_id
+            ));
+        }
+        self.database.delete_user(username)
+    }
+
+    pub fn create_epic(&self, mut epic: Epic) -> Result<u32> {
+        let _lock = self.lock()?;
+        epic.created = Some(Utc::now());
+        epic.updated = epic.created;
+        let epic_id = self.database.insert_epic(epic.clone())?;
+        self.record(Change::new(format!("create epic {}", epic_id)).epic(
+            epic_id,
+            None,
+            Some(epic),
+        ))?;
+        Ok(epic_id)
+    }
+
+    pub fn create_story(&self, mut story: Story, epic_id: u32) -> Result<u32> {
+        let _lock = self.lock()?;
+        story.created = Some(Utc::now());
+        story.updated = story.created;
+        let epic_before = self.database.get_epic(epic_id)?;
+        let story_id = self.database.insert_story(story.clone(), epic_id)?;
+        let epic_after = self.database.get_epic(epic_id)?;
+        self.record(
+            self.roll_up(
+                Change::new(format!("create story {}", story_id))
+                    .epic(epic_id, epic_before, epic_after)
+                    .story(story_id, None, Some(story)),
+            )?,
+        )?;
+        Ok(story_id)
+    }
+
+    pub fn delete_epic(&self, epic_id: u32) -> Result<()> {
+        let _lock = self.lock()?;
+        let epic = self.database.get_epic(epic_id)?;
+        let mut change = Change::new(format!("delete epic {}", epic_id));
+        for story_id in epic.iter().flat_map(|epic| epic.stories.iter()) {
+            change = change.story(*story_id, self.database.get_story(*story_id)?, None);
+        }
+        self.database.delete_epic(epic_id)?;
+        self.record(change.epic(epic_id, epic, None))
+    }
+
+    pub fn delete_story(&self, epic_id: u32, story_id: u32) -> Result<()> {
+        let _lock = self.lock()?;
+        let epic_before = self.database.get_epic(epic_id)?;
+        let story = self.database.get_story(story_id)?;
+        self.database.delete_story(epic_id, story_id)?;
+        let epic_after = self.database.get_epic(epic_id)?;
+        self.record(
+            self.roll_up(
+                Change::new(format!("delete story {}", story_id))
+                    .epic(epic_id, epic_before, epic_after)
+                    .story(story_id, story, None),
+            )?,
+        )
+    }
+
+    pub fn update_epic_details(
+        &self,
+        epic_id: u32,
+        name: String,
+        description: String,
+    ) -> Result<()> {
+        self.modify_epic(epic_id, format!("edit epic {}", epic_id), |epic| {
+            epic.name = name;
+            epic.description = description;
+            Ok(())
+        })
+    }
+
+    pub fn move_story(&self, story_id: u32, from_epic_id: u32, to_epic_id: u32) -> Result<()> {
+        let _lock = self.lock()?;
+        let from_before = self.database.get_epic(from_epic_id)?;
+        let to_before = self.database.get_epic(to_epic_id)?;
+        self.database
+            .move_story(story_id, from_epic_id, to_epic_id)?;
+        let from_after = self.database.get_epic(from_epic_id)?;
+        let to_after = self.database.get_epic(to_epic_id)?;
+        self.record(
+            self.roll_up(
+                Change::new(format!("move story {} to epic {}", story_id, to_epic_id))
+                    .epic(from_epic_id, from_before, from_after)
+                    .epic(to_epic_id, to_before, to_after),
+            )?,
+        )
+    }
 
     pub fn update_epic_status(&self, epic_id: u32, status: Status) -> Result<()> {
-        let mut db = self.read_db()?;
-        db.epics
-            .get_mut(&epic_id)
-            .ok_or_else(|| anyhow!("could not find epic in database for update epic status"))?
-            .status = status;
-        self.database.write_db(&db)?;
-        Ok(())
+        let description = format!("update status of epic {}", epic_id);
+        self.modify_epic(epic_id, description, |epic| {
+            self.workflow
+                .check_transition(&epic.status, &status)
+                .map_err(|error| anyhow!("{} for epic {}", error, epic_id))?;
+            epic.status = status;
+            Ok(())
+        })
+    }
+
+    pub fn update_story_details(
+        &self,
+        story_id: u32,
+        name: String,
+        description: String,
+    ) -> Result<()> {
+        self.modify_story(story_id, format!("edit story {}", story_id), |story| {
+            story.name = name;
+            story.description = description;
+            Ok(())
+        })
     }
 
     pub fn update_story_status(&self, story_id: u32, status: Status) -> Result<()> {
+        let description = format!("update status of story {}", story_id);
+        self.modify_story(story_id, description, |story| {
+            self.workflow
+                .check_transition(&story.status, &status)
+                .map_err(|error| anyhow!("{} for story {}", error, story_id))?;
+            story.status = status;
+            Ok(())
+        })
+    }
+
+    pub fn update_epic_priority(&self, epic_id: u32, priority: Priority) -> Result<()> {
+        let description = format!("update priority of epic {}", epic_id);
+        self.modify_epic(epic_id, description, |epic| {
+            epic.priority = priority;
+            Ok(())
+        })
+    }
+
+    pub fn update_story_priority(&self, story_id: u32, priority: Priority) -> Result<()> {
+        let description = format!("update priority of story {}", story_id);
+        self.modify_story(story_id, description, |story| {
+            story.priority = priority;
+            Ok(())
+        })
+    }
+
+    /// Sets or clears (`None`) the owner of an epic, the user has to be registered.
+    pub fn assign_epic(&self, epic_id: u32, owner: Option<String>) -> Result<()> {
+        let description = match &owner {
+            Some(owner) => format!("assign epic {} to {}", epic_id, owner),
+            None => format!("unassign epic {}", epic_id),
+        };
+        self.modify_epic(epic_id, description, |epic| {
+            self.check_user(owner.as_deref())?;
+            epic.owner = owner;
+            Ok(())
+        })
+    }
+
+    /// Sets or clears (`None`) the assignee of a story, the user has to be registered.
+    pub fn assign_story(&self, story_id: u32, assignee: Option<String>) -> Result<()> {
+        let description = match &assignee {
+            Some(assignee) => format!("assign story {} to {}", story_id, assignee),
+            None => format!("unassign story {}", story_id),
+        };
+        self.modify_story(story_id, description, |story| {
+            self.check_user(assignee.as_deref())?;
+            story.assignee = assignee;
+            Ok(())
+        })
+    }
+
+    /// Adds (`add`) or removes a label of an epic.
+    pub fn label_epic(&self, epic_id: u32, label: &str, add: bool) -> Result<()> {
+        let description = if add {
+            format!("add label {} to epic {}", label, epic_id)
+        } else {
+            format!("remove label {} from epic {}", label, epic_id)
+        };
+        self.modify_epic(epic_id, description, |epic| {
+            update_labels(&mut epic.labels, label, add)
+        })
+    }
+
+    /// Adds (`add`) or removes a label of a story.
+    pub fn label_story(&self, story_id: u32, label: &str, add: bool) -> Result<()> {
+        let description = if add {
+            format!("add label {} to story {}", label, story_id)
+        } else {
+            format!("remove label {} from story {}", label, story_id)
+        };
+        self.modify_story(story_id, description, |story| {
+            update_labels(&mut story.labels, label, add)
+        })
+    }
+
+    /// Appends a comment by the current actor to the discussion of a story.
+    pub fn add_comment(&self, story_id: u32, body: String) -> Result<()> {
+        if body.trim().is_empty() {
+            return Err(anyhow!("comment must not be empty"));
+        }
+        let description = format!("comment on story {}", story_id);
+        self.modify_story(story_id, description, |story| {
+            story.comments.push(Comment {
+                author: self.actor.clone(),
+                time: Utc::now(),
+                body,
+            });
+            Ok(())
+        })
+    }
+
+    /// Applies `modify` to an epic under the lock, marks it as updated and records the change.
+    fn modify_epic(
+        &self,
+        epic_id: u32,
+        description: String,
+        modify: impl FnOnce(&mut Epic) -> Result<()>,
+    ) -> Result<()> {
+        let _lock = self.lock()?;
+        let before = self
+            .database
+            .get_epic(epic_id)?
+            .ok_or_else(|| anyhow!("could not find epic {} in database", epic_id))?;
+        let mut epic = before.clone();
+        modify(&mut epic)?;
+        epic.updated = Some(Utc::now());
+        self.database.update_epic(epic_id, epic.clone())?;
+        self.record(Change::new(description).epic(epic_id, Some(before), Some(epic)))
+    }
+
+    /// Applies `modify` to a story under the lock, marks it as updated and records the change,
+    /// rolling up the status of its epic if the story status changed.
+    fn modify_story(
+        &self,
+        story_id: u32,
+        description: String,
+        modify: impl FnOnce(&mut Story) -> Result<()>,
+    ) -> Result<()> {
+        let _lock = self.lock()?;
+        let before = self
+            .database
+            .get_story(story_id)?
+            .ok_or_else(|| anyhow!("could not find story {} in database", story_id))?;
+        let mut story = before.clone();
+        modify(&mut story)?;
+        story.updated = Some(Utc::now());
+        self.database.update_story(story_id, story.clone())?;
+        let status_changed = story.status != before.status;
+        let change = Change::new(description).story(story_id, Some(before), Some(story));
+        if status_changed {
+            self.record(self.roll_up(change)?)
+        } else {
+            self.record(change)
+        }
+    }
+
+    fn check_user(&self, username: Option<&str>) -> Result<()> {
+        match username {
+            Some(username)
+                if !self
+                    .database
+                    .list_users()?
+                    .iter()
+                    .any(|user| user.username == username) =>
+            {
+                Err(anyhow!("could not find user {} in database", username))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Reverts the latest recorded change and returns its description, `None` if there is none.
+    pub fn undo(&self) -> Result<Option<String>> {
+        let _lock = self.lock()?;
+        let mut history = self.database.read_history()?;
+        let Some(change) = history.undo.pop() else {
+            return Ok(None);
+        };
+        self.apply_change(&change, true)?;
+        self.log_fields(&change, true)?;
+        let description = change.description.clone();
+        history.redo.push(change);
+        self.database.write_history(&history)?;
+        Ok(Some(description))
+    }
+
+    /// Applies the latest undone change again and returns its description, `None` if there is none.
+    pub fn redo(&self) -> Result<Option<String>> {
+        let _lock = self.lock()?;
+        let mut history = self.database.read_history()?;
+        let Some(change) = history.redo.pop() else {
+            return Ok(None);
+        };
+        self.apply_change(&change, false)?;
+        self.log_fields(&change, false)?;
+        let description = change.description.clone();
+        history.undo.push(change);
+        self.database.write_history(&history)?;
+        Ok(Some(description))
+    }
+
+    /// With status rollup enabled, updates the epics affected by a story change to the status
+    /// derived from their stories and adds them to the change, so undo restores them as well.
+    /// Epics keep their status when the workflow does not allow the change.
+    fn roll_up(&self, mut change: Change) -> Result<Change> {
+        if !self.workflow.rollup {
+            return Ok(change);
+        }
+        let epics = self.database.list_epics()?;
+        let mut epic_ids: Vec<u32> = change.epics.iter().map(|item| item.id).collect();
+        for item in &change.stories {
+            if let Some((epic_id, _)) = epics
+                .iter()
+                .find(|(_, epic)| epic.stories.contains(&item.id))
+            {
+                epic_ids.push(*epic_id);
+            }
+        }
+        epic_ids.sort();
+        epic_ids.dedup();
+        for epic_id in epic_ids {
+            let Some(before) = epics.get(&epic_id) else {
+                continue;
+            };
+            let mut statuses = vec![];
+            for story_id in &before.stories {
+                if let Some(story) = self.database.get_story(*story_id)? {
+                    statuses.push(story.status);
+                }
+            }
+            let Some(status) = rollup_status(&statuses) else {
+                continue;
+            };
+            if status == before.status
+                || self
+                    .workflow
+                    .check_transition(&before.status, &status)
+                    .is_err()
+            {
+                continue;
+            }
+            let mut epic = before.clone();
+            epic.status = status;
+            epic.updated = Some(Utc::now());
+            self.database.update_epic(epic_id, epic.clone())?;
+            match change.epics.iter_mut().find(|item| item.id == epic_id) {
+                Some(item) => item.after = Some(epic),
+                None => change = change.epic(epic_id, Some(before.clone()), Some(epic)),
+            }
+        }
+        Ok(change)
+    }
+
+    /// Runs `f` under one lock and records all changes it makes as a single change, so that e.g.
+    /// an import is undone at once and does not push earlier changes out of the history.
+    pub fn batch<T>(&self, description: String, f: impl FnOnce() -> Result<T>) -> Result<T> {
+        if self.batch.borrow().is_some() {
+            return f();
+        }
+        let _lock = self.database.lock()?;
+        *self.batch.borrow_mut() = Some(Change::new(description));
+        let result = f();
+        let change = self.batch.borrow_mut().take();
+        // whatever `f` did before failing is still recorded so it can be undone
+        if let Some(change) = change.filter(|change| !change.is_empty()) {
+            self.push_history(change)?;
+        }
+        result
+    }
+
+    /// Takes the database lock, unless a running batch already holds it.
+    fn lock(&self) -> Result<Option<DatabaseLock>> {
+        if self.batch.borrow().is_some() {
+            return Ok(None);
+        }
+        Ok(Some(self.database.lock()?))
+    }
+
+    fn record(&self, change: Change) -> Result<()> {
+        self.log_fields(&change, false)?;
+        if let Some(batch) = self.batch.borrow_mut().as_mut() {
+            batch.merge(change);
+            return Ok(());
+        }
+        self.push_history(change)
+    }
+
+    fn push_history(&self, change: Change) -> Result<()> {
+        let mut history = self.database.read_history()?;
+        history.undo.push(change);
+        if history.undo.len() > MAX_HISTORY {
+            history.undo.remove(0);
+        }
+        history.redo.clear();
+        self.database.write_history(&history)
+    }
+
+    /// Appends the fields that a change (or its undo) modified to the change log.
+    fn log_fields(&self, change: &Change, undo: bool) -> Result<()> {
+        let time = Utc::now();
+        let mut entries = vec![];
+        for item in &change.epics {
+            entries.extend(field_changes(item, undo, time, &self.actor)?);
+        }
+        for item in &change.stories {
+            entries.extend(field_changes(item, undo, time, &self.actor)?);
+        }
+        self.database.append_changelog(&entries)
+    }
+
+    fn apply_change(&self, change: &Change, undo: bool) -> Result<()> {
         let mut db = self.read_db()?;
-        db.stories
-            .get_mut(&story_id)
-            .ok_or_else(|| anyhow!("could not find epic in database for update story status"))?
-            .status = status;
-        self.database.write_db(&db)?;
-        Ok(())
+        apply_items(&mut db.epics, &change.epics, undo)
+            .map_err(|id| conflict_error(change, undo, "epic", id))?;
+        apply_items(&mut db.stories, &change.stories, undo)
+            .map_err(|id| conflict_error(change, undo, "story", id))?;
+        self.database.write_db(&db)
+    }
+}
+const MAX_HISTORY: usize = 100;
+fn update_labels(labels: &mut BTreeSet<String>, label: &str, add: bool) -> Result<()> {
+    if add {
+        validate_label(label).map_err(|error| anyhow!(error))?;
+        if !labels.insert(label.to_owned()) {
+            return Err(anyhow!("label {} is already set", label));
+        }
+    } else if !labels.remove(label) {
+        return Err(anyhow!("label {} is not set", label));
+    }
+    Ok(())
+}
+/// Lists the fields that differ between both sides of an item change, a created or deleted item
+/// is a single entry. The item's own timestamps are left out as they change every time, and so
+/// are comments, which carry their own author and time.
+fn field_changes<T: Serialize>(
+    item: &ItemChange<T>,
+    undo: bool,
+    time: DateTime<Utc>,
+    actor: &Option<String>,
+) -> Result<Vec<FieldChange>> {
+    let (from, to) = if undo {
+        (&item.after, &item.before)
+    } else {
+        (&item.before, &item.after)
+    };
+    let entry = |field: &str, old_value: Option<&Value>, new_value: Option<&Value>| FieldChange {
+        item_id: item.id,
+        field: field.to_owned(),
+        old_value: old_value.filter(|v| !v.is_null()).map(Value::to_string),
+        new_value: new_value.filter(|v| !v.is_null()).map(Value::to_string),
+        time,
+        actor: actor.clone(),
+    };
+    let (from, to) = match (from, to) {
+        (Some(from), Some(to)) => (serde_json::to_value(from)?, serde_json::to_value(to)?),
+        (None, _) => return Ok(vec![entry("created", None, None)]),
+        (_, None) => return Ok(vec![entry("deleted", None, None)]),
+    };
+    Ok(from
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(field, _)| !matches!(field.as_str(), "created" | "updated" | "comments"))
+        .filter(|(field, value)| to.get(field.as_str()) != Some(value))
+        .map(|(field, value)| entry(field, Some(value), to.get(field.as_str())))
+        .collect())
+}
+/// Moves every item from one side of its change to the other, fails with the id of the first
+/// item that no longer matches what the change left behind.
+fn apply_items<T: Clone + PartialEq>(
+    items: &mut HashMap<u32, T>,
+    changes: &[ItemChange<T>],
+    undo: bool,
+) -> std::result::Result<(), u32> {
+    for change in changes {
+        let (expected, target) = if undo {
+            (&change.after, &change.before)
+        } else {
+            (&change.before, &change.after)
+        };
+        if items.get(&change.id) != expected.as_ref() {
+            return Err(change.id);
+        }
+        match target {
+            Some(item) => items.insert(change.id, item.clone()),
+            None => items.remove(&change.id),
+        };
     }
+    Ok(())
+}
+fn conflict_error(change: &Change, undo: bool, kind: &str, id: u32) -> anyhow::Error {
+    anyhow!(
+        "cannot {} '{}': {} {} was changed since",
+        if undo { "undo" } else { "redo" },
+        change.description,
+        kind,
+        id
+    )
 }
 pub mod test_utils {
     use std::{cell::RefCell, collections::HashMap};
@@ -144,6 +995,8 @@ pub mod test_utils {
     use super::*;
     pub struct MockDB {
         last_written_state: RefCell<DBState>,
+        history: RefCell<History>,
+        changelog: RefCell<Vec<FieldChange>>,
     }
     #[allow(dead_code)] //used in test
     impl MockDB {
@@ -153,7 +1006,10 @@ pub mod test_utils {
                     last_item_id: 0,
                     epics: HashMap::new(),
                     stories: HashMap::new(),
+                    users: vec![],
                 }),
+                history: RefCell::new(History::default()),
+                changelog: RefCell::new(vec![]),
             }
         }
     }
@@ -167,6 +1023,25 @@ pub mod test_utils {
             *latest_state.borrow_mut() = db_state.clone();
             Ok(())
         }
+        fn read_history(&self) -> Result<History> {
+            Ok(self.history.borrow().clone())
+        }
+        fn write_history(&self, history: &History) -> Result<()> {
+            *self.history.borrow_mut() = history.clone();
+            Ok(())
+        }
+        fn read_changelog(&self, item_id: u32) -> Result<Vec<FieldChange>> {
+            let changelog = self.changelog.borrow();
+            Ok(changelog
+                .iter()
+                .filter(|entry| entry.item_id == item_id)
+                .cloned()
+                .collect())
+        }
+        fn append_changelog(&self, entries: &[FieldChange]) -> Result<()> {
+            self.changelog.borrow_mut().extend_from_slice(entries);
+            Ok(())
+        }
     }
 }
 
@@ -177,14 +1052,12 @@ mod test {
 
     #[test]
     fn create_epic_should_work() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
         let epic = Epic::new("".to_owned(), "".to_owned());
 
         let result = db.create_epic(epic.clone());
 
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
 
         let id = result.unwrap();
         let db_state = db.read_db().unwrap();
@@ -193,37 +1066,40 @@ mod test {
 
         assert_eq!(id, expected_id);
         assert_eq!(db_state.last_item_id, expected_id);
-        assert_eq!(db_state.epics.get(&id), Some(&epic));
+
+        let created = &db_state.epics[&id];
+        assert!(created.created.is_some());
+        assert_eq!(created.updated, created.created);
+        let mut expected = epic;
+        expected.created = created.created;
+        expected.updated = created.updated;
+        assert_eq!(created, &expected);
     }
 
     #[test]
     fn create_story_should_error_if_invalid_epic_id() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
         let story = Story::new("".to_owned(), "".to_owned());
 
         let non_existent_epic_id = 999;
 
         let result = db.create_story(story, non_existent_epic_id);
-        assert_eq!(result.is_err(), true);
+        assert!(result.is_err());
     }
 
     #[test]
     fn create_story_should_work() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
         let epic = Epic::new("".to_owned(), "".to_owned());
         let story = Story::new("".to_owned(), "".to_owned());
 
         let result = db.create_epic(epic);
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
 
         let epic_id = result.unwrap();
 
         let result = db.create_story(story.clone(), epic_id);
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
 
         let id = result.unwrap();
         let db_state = db.read_db().unwrap();
@@ -232,45 +1108,44 @@ mod test {
 
         assert_eq!(id, expected_id);
         assert_eq!(db_state.last_item_id, expected_id);
-        assert_eq!(
-            db_state.epics.get(&epic_id).unwrap().stories.contains(&id),
-            true
-        );
-        assert_eq!(db_state.stories.get(&id), Some(&story));
+        assert!(db_state.epics.get(&epic_id).unwrap().stories.contains(&id));
+
+        let created = &db_state.stories[&id];
+        assert!(created.created.is_some());
+        let mut expected = story;
+        expected.created = created.created;
+        expected.updated = created.updated;
+        assert_eq!(created, &expected);
     }
 
     #[test]
     fn delete_epic_should_error_if_invalid_epic_id() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
 
         let non_existent_epic_id = 999;
 
         let result = db.delete_epic(non_existent_epic_id);
-        assert_eq!(result.is_err(), true);
+        assert!(result.is_err());
     }
 
     #[test]
     fn delete_epic_should_work() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
         let epic = Epic::new("".to_owned(), "".to_owned());
         let story = Story::new("".to_owned(), "".to_owned());
 
         let result = db.create_epic(epic);
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
 
         let epic_id = result.unwrap();
 
         let result = db.create_story(story, epic_id);
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
 
         let story_id = result.unwrap();
 
         let result = db.delete_epic(epic_id);
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
 
         let db_state = db.read_db().unwrap();
 
@@ -283,139 +1158,194 @@ mod test {
 
     #[test]
     fn delete_story_should_error_if_invalid_epic_id() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
         let epic = Epic::new("".to_owned(), "".to_owned());
         let story = Story::new("".to_owned(), "".to_owned());
 
         let result = db.create_epic(epic);
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
 
         let epic_id = result.unwrap();
 
         let result = db.create_story(story, epic_id);
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
 
         let story_id = result.unwrap();
 
         let non_existent_epic_id = 999;
 
         let result = db.delete_story(non_existent_epic_id, story_id);
-        assert_eq!(result.is_err(), true);
+        assert!(result.is_err());
     }
 
     #[test]
     fn delete_story_should_error_if_story_not_found_in_epic() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
         let epic = Epic::new("".to_owned(), "".to_owned());
         let story = Story::new("".to_owned(), "".to_owned());
 
         let result = db.create_epic(epic);
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
 
         let epic_id = result.unwrap();
 
         let result = db.create_story(story, epic_id);
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
 
         let non_existent_story_id = 999;
 
         let result = db.delete_story(epic_id, non_existent_story_id);
-        assert_eq!(result.is_err(), true);
+        assert!(result.is_err());
     }
 
     #[test]
     fn delete_story_should_work() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
         let epic = Epic::new("".to_owned(), "".to_owned());
         let story = Story::new("".to_owned(), "".to_owned());
 
         let result = db.create_epic(epic);
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
 
         let epic_id = result.unwrap();
 
         let result = db.create_story(story, epic_id);
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
 
         let story_id = result.unwrap();
 
         let result = db.delete_story(epic_id, story_id);
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
 
         let db_state = db.read_db().unwrap();
 
         let expected_last_id = 2;
 
         assert_eq!(db_state.last_item_id, expected_last_id);
+        assert!(!db_state
+            .epics
+            .get(&epic_id)
+            .unwrap()
+            .stories
+            .contains(&story_id));
+        assert_eq!(db_state.stories.get(&story_id), None);
+    }
+
+    #[test]
+    fn move_story_should_error_if_invalid_ids() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_1 = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let epic_2 = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_1)
+            .unwrap();
+
+        assert!(db.move_story(story_id, epic_1, 999).is_err());
+        assert!(db.move_story(story_id, 999, epic_2).is_err());
+        assert!(db.move_story(story_id, epic_2, epic_1).is_err());
+        assert!(db.move_story(story_id, epic_1, epic_1).is_err());
+        assert!(db.move_story(999, epic_1, epic_2).is_err());
+    }
+
+    #[test]
+    fn move_story_should_work() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_1 = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let epic_2 = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_1)
+            .unwrap();
+        let other_story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_2)
+            .unwrap();
+
+        let result = db.move_story(story_id, epic_1, epic_2);
+        assert!(result.is_ok());
+
+        let db_state = db.read_db().unwrap();
+        assert!(db_state.epics[&epic_1].stories.is_empty());
         assert_eq!(
-            db_state
-                .epics
-                .get(&epic_id)
-                .unwrap()
-                .stories
-                .contains(&story_id),
-            false
+            db_state.epics[&epic_2].stories,
+            vec![other_story_id, story_id]
         );
-        assert_eq!(db_state.stories.get(&story_id), None);
+        assert!(db_state.stories.contains_key(&story_id));
+
+        db.undo().unwrap();
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.epics[&epic_1].stories, vec![story_id]);
+        assert_eq!(db_state.epics[&epic_2].stories, vec![other_story_id]);
     }
 
     #[test]
     fn update_epic_status_should_error_if_invalid_epic_id() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
 
         let non_existent_epic_id = 999;
 
         let result = db.update_epic_status(non_existent_epic_id, Status::Closed);
-        assert_eq!(result.is_err(), true);
+        assert!(result.is_err());
     }
 
     #[test]
     fn update_epic_status_should_work() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
         let epic = Epic::new("".to_owned(), "".to_owned());
 
         let result = db.create_epic(epic);
 
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
 
         let epic_id = result.unwrap();
 
         let result = db.update_epic_status(epic_id, Status::Closed);
 
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
 
         let db_state = db.read_db().unwrap();
 
         assert_eq!(db_state.epics.get(&epic_id).unwrap().status, Status::Closed);
     }
 
+    #[test]
+    fn update_epic_details_should_work() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+
+        let result = db.update_epic_details(epic_id, "name".to_owned(), "description".to_owned());
+        assert!(result.is_ok());
+
+        let epic = db.get_epic(epic_id).unwrap().unwrap();
+        assert_eq!(epic.name, "name");
+        assert_eq!(epic.description, "description");
+        assert_eq!(epic.status, Status::Open);
+
+        let result = db.update_epic_details(999, "".to_owned(), "".to_owned());
+        assert!(result.is_err());
+    }
+
     #[test]
     fn update_story_status_should_error_if_invalid_story_id() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
 
         let non_existent_story_id = 999;
 
         let result = db.update_story_status(non_existent_story_id, Status::Closed);
-        assert_eq!(result.is_err(), true);
+        assert!(result.is_err());
     }
 
     #[test]
     fn update_story_status_should_work() {
-        let db = JiraDatabase {
-            database: Box::new(MockDB::new()),
-        };
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
         let epic = Epic::new("".to_owned(), "".to_owned());
         let story = Story::new("".to_owned(), "".to_owned());
 
@@ -429,7 +1359,7 @@ mod test {
 
         let result = db.update_story_status(story_id, Status::Closed);
 
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
 
         let db_state = db.read_db().unwrap();
 
@@ -439,62 +1369,769 @@ mod test {
         );
     }
 
+    #[test]
+    fn story_changes_should_roll_up_epic_status() {
+        let db = JiraDatabase {
+            workflow: Workflow {
+                rollup: true,
+                ..Workflow::default()
+            },
+            ..JiraDatabase::with_database(Box::new(MockDB::new()))
+        };
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let other_epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let other_story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let epic_status = || db.get_epic(epic_id).unwrap().unwrap().status;
+
+        db.update_story_status(story_id, Status::InProgress)
+            .unwrap();
+        assert_eq!(epic_status(), Status::InProgress);
+
+        db.update_story_status(story_id, Status::Closed).unwrap();
+        db.update_story_status(other_story_id, Status::Resolved)
+            .unwrap();
+        assert_eq!(epic_status(), Status::Resolved);
+
+        db.delete_story(epic_id, other_story_id).unwrap();
+        assert_eq!(epic_status(), Status::Closed);
+
+        db.undo().unwrap();
+        assert_eq!(epic_status(), Status::Resolved);
+
+        db.move_story(story_id, epic_id, other_epic_id).unwrap();
+        assert_eq!(epic_status(), Status::Resolved);
+        assert_eq!(
+            db.get_epic(other_epic_id).unwrap().unwrap().status,
+            Status::Closed
+        );
+
+        db.create_story(Story::new("".to_owned(), "".to_owned()), other_epic_id)
+            .unwrap();
+        assert_eq!(
+            db.get_epic(other_epic_id).unwrap().unwrap().status,
+            Status::InProgress
+        );
+    }
+
+    #[test]
+    fn story_changes_should_only_roll_up_allowed_epic_status() {
+        let db = JiraDatabase {
+            workflow: Workflow {
+                statuses: vec![Status::Open, Status::InProgress, Status::Closed],
+                transitions: std::collections::HashMap::from([(Status::Closed, vec![])]),
+                rollup: true,
+            },
+            ..JiraDatabase::with_database(Box::new(MockDB::new()))
+        };
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let epic_status = |epic_id| db.get_epic(epic_id).unwrap().unwrap().status;
+
+        db.update_story_status(story_id, Status::Closed).unwrap();
+        assert_eq!(epic_status(epic_id), Status::Closed);
+
+        db.create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        assert_eq!(epic_status(epic_id), Status::Closed);
+
+        let other_epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let mut story = Story::new("".to_owned(), "".to_owned());
+        story.status = Status::Resolved;
+        db.create_story(story, other_epic_id).unwrap();
+        assert_eq!(epic_status(other_epic_id), Status::Open);
+    }
+
+    #[test]
+    fn story_changes_should_not_roll_up_epic_status_by_default() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        db.update_story_status(story_id, Status::Closed).unwrap();
+        assert_eq!(db.get_epic(epic_id).unwrap().unwrap().status, Status::Open);
+    }
+
+    #[test]
+    fn open_stories_should_list_unfinished_stories() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let mut story_ids = vec![];
+        for status in Status::ALL {
+            let story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            db.update_story_status(story_id, status).unwrap();
+            story_ids.push(story_id);
+        }
+
+        assert_eq!(db.open_stories(epic_id).unwrap(), story_ids[..2]);
+        assert!(db.open_stories(999).is_err());
+    }
+
+    #[test]
+    fn update_status_should_reject_transitions_not_in_workflow() {
+        let db = JiraDatabase {
+            workflow: Workflow {
+                statuses: Status::ALL.to_vec(),
+                transitions: HashMap::from([
+                    (Status::Open, vec![Status::InProgress]),
+                    (Status::Closed, vec![Status::Resolved]),
+                ]),
+                ..Workflow::default()
+            },
+            ..JiraDatabase::with_database(Box::new(MockDB::new()))
+        };
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let error = db
+            .update_story_status(story_id, Status::Closed)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "cannot change status from \"Open\" to \"Closed\" (allowed: \"InProgress\") for story {}",
+                story_id
+            )
+        );
+        assert!(db.update_epic_status(epic_id, Status::Resolved).is_err());
+        assert_eq!(
+            db.read_db().unwrap().stories[&story_id].status,
+            Status::Open
+        );
+
+        db.update_story_status(story_id, Status::InProgress)
+            .unwrap();
+        db.update_story_status(story_id, Status::Closed).unwrap();
+        assert!(db.update_story_status(story_id, Status::Open).is_err());
+        assert!(db.update_story_status(story_id, Status::Resolved).is_ok());
+    }
+
+    #[test]
+    fn update_priority_should_error_if_invalid_id() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+
+        assert!(db.update_epic_priority(999, Priority::High).is_err());
+        assert!(db.update_story_priority(999, Priority::High).is_err());
+    }
+
+    #[test]
+    fn update_priority_should_work() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        assert!(db.update_epic_priority(epic_id, Priority::Lowest).is_ok());
+        assert!(db
+            .update_story_priority(story_id, Priority::Highest)
+            .is_ok());
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.epics[&epic_id].priority, Priority::Lowest);
+        assert_eq!(db_state.stories[&story_id].priority, Priority::Highest);
+
+        db.undo().unwrap();
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.stories[&story_id].priority, Priority::Medium);
+    }
+
+    #[test]
+    fn assign_story_should_only_accept_registered_users() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        assert!(db.assign_story(story_id, Some("alice".to_owned())).is_err());
+
+        db.add_user(User::new("alice".to_owned(), "Alice".to_owned()))
+            .unwrap();
+        assert!(db
+            .add_user(User::new("alice".to_owned(), "".to_owned()))
+            .is_err());
+        db.assign_story(story_id, Some("alice".to_owned())).unwrap();
+        db.assign_epic(epic_id, Some("alice".to_owned())).unwrap();
+        assert!(db.assign_story(999, Some("alice".to_owned())).is_err());
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(
+            db_state.stories[&story_id].assignee.as_deref(),
+            Some("alice")
+        );
+        assert_eq!(db_state.epics[&epic_id].owner.as_deref(), Some("alice"));
+
+        assert!(db.remove_user("alice").is_err());
+        db.assign_story(story_id, None).unwrap();
+        db.undo().unwrap();
+        assert!(db.remove_user("alice").is_err());
+        db.assign_epic(epic_id, None).unwrap();
+        db.assign_story(story_id, None).unwrap();
+        db.remove_user("alice").unwrap();
+        assert!(db.list_users().unwrap().is_empty());
+        assert!(db.remove_user("alice").is_err());
+    }
+
+    #[test]
+    fn label_should_add_and_remove_labels() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        db.label_epic(epic_id, "frontend", true).unwrap();
+        db.label_story(story_id, "tech-debt", true).unwrap();
+        db.label_story(story_id, "customer-x", true).unwrap();
+        assert!(db.label_story(story_id, "tech-debt", true).is_err());
+        assert!(db.label_story(story_id, "two words", true).is_err());
+        assert!(db.label_story(story_id, "", true).is_err());
+        assert!(db.label_epic(999, "frontend", true).is_err());
+
+        let db_state = db.read_db().unwrap();
+        assert!(db_state.epics[&epic_id].labels.contains("frontend"));
+        assert_eq!(
+            db_state.stories[&story_id]
+                .labels
+                .iter()
+                .collect::<Vec<_>>(),
+            vec!["customer-x", "tech-debt"]
+        );
+
+        db.label_story(story_id, "tech-debt", false).unwrap();
+        assert!(db.label_story(story_id, "tech-debt", false).is_err());
+        db.undo().unwrap();
+        assert!(db
+            .get_story(story_id)
+            .unwrap()
+            .unwrap()
+            .labels
+            .contains("tech-debt"));
+    }
+
+    #[test]
+    fn update_story_details_should_work() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        db.update_story_status(story_id, Status::InProgress)
+            .unwrap();
+
+        let result = db.update_story_details(story_id, "name".to_owned(), "description".to_owned());
+        assert!(result.is_ok());
+
+        let story = db.get_story(story_id).unwrap().unwrap();
+        assert_eq!(story.name, "name");
+        assert_eq!(story.description, "description");
+        assert_eq!(story.status, Status::InProgress);
+
+        let result = db.update_story_details(999, "".to_owned(), "".to_owned());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn get_and_list_should_return_items() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        assert_eq!(
+            db.get_epic(epic_id).unwrap().unwrap().stories,
+            vec![story_id]
+        );
+        assert_eq!(db.get_story(story_id).unwrap().unwrap().name, "story");
+        assert_eq!(db.get_epic(999).unwrap(), None);
+        assert_eq!(db.get_story(999).unwrap(), None);
+        assert_eq!(db.list_epics().unwrap().len(), 1);
+        assert_eq!(db.list_stories().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn undo_should_restore_deleted_epic_with_its_stories() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let before = db.read_db().unwrap();
+
+        db.delete_epic(epic_id).unwrap();
+        assert_eq!(db.undo().unwrap(), Some(format!("delete epic {}", epic_id)));
+        assert_eq!(db.read_db().unwrap(), before);
+
+        assert_eq!(db.redo().unwrap(), Some(format!("delete epic {}", epic_id)));
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.epics.get(&epic_id), None);
+        assert_eq!(db_state.stories.get(&story_id), None);
+    }
+
+    #[test]
+    fn undo_should_walk_back_through_all_changes() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        db.update_story_status(story_id, Status::Closed).unwrap();
+        db.update_epic_status(epic_id, Status::Resolved).unwrap();
+        db.delete_story(epic_id, story_id).unwrap();
+
+        while db.undo().unwrap().is_some() {}
+
+        let db_state = db.read_db().unwrap();
+        assert!(db_state.epics.is_empty());
+        assert!(db_state.stories.is_empty());
+        assert_eq!(db.redo().unwrap(), Some(format!("create epic {}", epic_id)));
+    }
+
+    #[test]
+    fn batch_should_be_undone_as_one_change() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+
+        db.batch("import".to_owned(), || {
+            for _ in 0..MAX_HISTORY {
+                let other_id = db.create_epic(Epic::new("".to_owned(), "".to_owned()))?;
+                db.create_story(Story::new("".to_owned(), "".to_owned()), other_id)?;
+            }
+            db.update_epic_status(epic_id, Status::Closed)
+        })
+        .unwrap();
+        assert_eq!(db.database.read_history().unwrap().undo.len(), 2);
+
+        assert_eq!(db.undo().unwrap(), Some("import".to_owned()));
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.epics.len(), 1);
+        assert_eq!(db_state.epics[&epic_id].status, Status::Open);
+        assert!(db_state.stories.is_empty());
+        assert_eq!(db.undo().unwrap(), Some(format!("create epic {}", epic_id)));
+    }
+
+    #[test]
+    fn new_change_should_clear_redo() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        db.create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        db.undo().unwrap();
+        db.create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+
+        assert_eq!(db.redo().unwrap(), None);
+    }
+
+    #[test]
+    fn undo_should_fail_if_item_changed_since() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        db.update_epic_status(epic_id, Status::Closed).unwrap();
+
+        // change the epic behind the back of the history
+        db.database
+            .update_epic(epic_id, Epic::new("other".to_owned(), "".to_owned()))
+            .unwrap();
+
+        assert!(db.undo().is_err());
+        assert_eq!(db.get_epic(epic_id).unwrap().unwrap().name, "other");
+        assert_eq!(db.database.read_history().unwrap().undo.len(), 2);
+    }
+
+    #[test]
+    fn changes_should_be_logged_per_field_with_actor() {
+        let db = JiraDatabase {
+            actor: Some("alice".to_owned()),
+            ..JiraDatabase::with_database(Box::new(MockDB::new()))
+        };
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let created = db.get_epic(epic_id).unwrap().unwrap().updated;
+        db.update_epic_status(epic_id, Status::Resolved).unwrap();
+        db.update_epic_details(epic_id, "name".to_owned(), "".to_owned())
+            .unwrap();
+        assert!(db.get_epic(epic_id).unwrap().unwrap().updated >= created);
+        db.undo().unwrap();
+
+        let changes: Vec<(String, Option<String>, Option<String>)> = db
+            .get_item_history(epic_id)
+            .unwrap()
+            .into_iter()
+            .map(|change| {
+                assert_eq!(change.actor.as_deref(), Some("alice"));
+                (change.field, change.old_value, change.new_value)
+            })
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                ("created".to_owned(), None, None),
+                (
+                    "status".to_owned(),
+                    Some("\"Open\"".to_owned()),
+                    Some("\"Resolved\"".to_owned())
+                ),
+                (
+                    "name".to_owned(),
+                    Some("\"\"".to_owned()),
+                    Some("\"name\"".to_owned())
+                ),
+                (
+                    "name".to_owned(),
+                    Some("\"name\"".to_owned()),
+                    Some("\"\"".to_owned())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn add_comment_should_append_comment_by_actor() {
+        let db = JiraDatabase {
+            actor: Some("alice".to_owned()),
+            ..JiraDatabase::with_database(Box::new(MockDB::new()))
+        };
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        assert!(db.add_comment(story_id, " ".to_owned()).is_err());
+        assert!(db.add_comment(999, "first".to_owned()).is_err());
+        db.add_comment(story_id, "first".to_owned()).unwrap();
+        db.add_comment(story_id, "second".to_owned()).unwrap();
+
+        let comments = db.get_story(story_id).unwrap().unwrap().comments;
+        let bodies: Vec<&str> = comments.iter().map(|c| c.body.as_str()).collect();
+        assert_eq!(bodies, vec!["first", "second"]);
+        assert_eq!(comments[0].author.as_deref(), Some("alice"));
+
+        db.undo().unwrap();
+        assert_eq!(db.get_story(story_id).unwrap().unwrap().comments.len(), 1);
+    }
+
     mod database {
         use super::*;
-        use std::fs::remove_file;
+        use std::fs::{self, remove_file};
         #[test]
         fn read_db_should_fail_with_invalid_path() {
-            let db_path = JSONFileDatabase {
-                file_path: "invalid_path".to_owned(),
-            };
-            assert_eq!(db_path.read_db().is_err(), true);
+            let db_path = JSONFileDatabase::new("invalid_path".to_owned());
+            assert!(db_path.read_db().is_err());
         }
         #[test]
         fn read_db_should_fail_with_invalid_json() {
             let mut tempfile = tempfile::NamedTempFile::new().unwrap();
             let content = r#"{ "last_item_id": 0 epics: {} stories {} }"#;
             write!(tempfile, "{content}").unwrap();
-            let db_path = JSONFileDatabase {
-                file_path: tempfile.path().to_str().unwrap().to_owned(),
-            };
+            let db_path = JSONFileDatabase::new(tempfile.path().to_str().unwrap().to_owned());
             println!("TempFilePath is: {}", db_path.file_path);
             let result = db_path.read_db();
             remove_file(db_path.file_path).unwrap();
-            assert_eq!(result.is_err(), true);
+            assert!(result.is_err());
         }
         #[test]
         fn read_db_should_parse_json_file() {
             let mut tempfile = tempfile::NamedTempFile::new().unwrap();
             let content = r#"{ "last_item_id": 0, "epics": {}, "stories": {} }"#;
             write!(tempfile, "{content}").unwrap();
-            let db_path = JSONFileDatabase {
-                file_path: tempfile.path().to_str().unwrap().to_owned(),
-            };
+            let db_path = JSONFileDatabase::new(tempfile.path().to_str().unwrap().to_owned());
             println!("TempFilePath is: {}", db_path.file_path);
             let result = db_path.read_db();
             remove_file(db_path.file_path).unwrap();
-            assert_eq!(result.is_ok(), true);
+            assert!(result.is_ok());
         }
         #[test]
         fn write_db_should_work() {
             let mut tempfile_read = tempfile::NamedTempFile::new().unwrap();
             let content = r#"{ "last_item_id": 0, "epics": {}, "stories": {} }"#;
             write!(tempfile_read, "{content}").unwrap();
-            let db_path = JSONFileDatabase {
-                file_path: tempfile_read.path().to_str().unwrap().to_owned(),
-            };
+            let db_path = JSONFileDatabase::new(tempfile_read.path().to_str().unwrap().to_owned());
             println!("TempFilePath is: {}", db_path.file_path);
             let db = db_path.read_db().unwrap();
             remove_file(db_path.file_path).unwrap();
 
             let tempfile_write = tempfile::NamedTempFile::new().unwrap();
-            let db_path = JSONFileDatabase {
-                file_path: tempfile_write.path().to_str().unwrap().to_owned(),
-            };
+            let db_path = JSONFileDatabase::new(tempfile_write.path().to_str().unwrap().to_owned());
             println!("TempFilePath is: {}", db_path.file_path);
             let write_result = db_path.write_db(&db);
             remove_file(db_path.file_path).unwrap();
-            assert_eq!(write_result.is_ok(), true);
+            assert!(write_result.is_ok());
+        }
+        #[test]
+        fn write_db_should_not_leave_temp_file() {
+            let dir = tempfile::tempdir().unwrap();
+            let db_path =
+                JSONFileDatabase::new(dir.path().join("db.json").to_str().unwrap().to_owned());
+            let db = DBState {
+                last_item_id: 0,
+                epics: HashMap::new(),
+                stories: HashMap::new(),
+                users: vec![],
+            };
+
+            assert!(db_path.write_db(&db).is_ok());
+            assert!(db_path.read_db().is_ok());
+            assert!(!Path::new(&format!("{}.tmp", db_path.file_path)).exists());
+        }
+        #[test]
+        fn failed_write_db_should_keep_previous_state() {
+            let dir = tempfile::tempdir().unwrap();
+            let db_path =
+                JSONFileDatabase::new(dir.path().join("db.json").to_str().unwrap().to_owned());
+            let mut db = DBState {
+                last_item_id: 1,
+                epics: HashMap::new(),
+                stories: HashMap::new(),
+                users: vec![],
+            };
+            db.epics
+                .insert(1, Epic::new("name".to_owned(), "description".to_owned()));
+            db_path.write_db(&db).unwrap();
+
+            // a directory in place of the temp file makes the next write fail halfway
+            std::fs::create_dir(format!("{}.tmp", db_path.file_path)).unwrap();
+            db.last_item_id = 2;
+            db.epics.clear();
+            assert!(db_path.write_db(&db).is_err());
+
+            let db_state = db_path.read_db().unwrap();
+            assert_eq!(db_state.last_item_id, 1);
+            assert_eq!(
+                db_state.epics.get(&1),
+                Some(&Epic::new("name".to_owned(), "description".to_owned()))
+            );
+        }
+        fn fixture_state() -> DBState {
+            let mut epic = Epic::new("Login".to_owned(), "User login flow".to_owned());
+            epic.status = Status::InProgress;
+            epic.stories = vec![3, 2];
+            let mut story = Story::new("Login form".to_owned(), "Email and password".to_owned());
+            story.status = Status::Closed;
+            DBState {
+                last_item_id: 3,
+                epics: HashMap::from([(1, epic)]),
+                stories: HashMap::from([
+                    (2, Story::new("Password reset".to_owned(), "".to_owned())),
+                    (3, story),
+                ]),
+                users: vec![],
+            }
+        }
+        #[test]
+        fn write_db_should_upgrade_every_fixture_version() {
+            let fixtures_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+            let mut versions = vec![];
+            for entry in fs::read_dir(fixtures_dir).unwrap() {
+                let path = entry.unwrap().path();
+                let file_name = path.file_name().unwrap().to_str().unwrap();
+                let Some(version) = file_name
+                    .strip_prefix("db_v")
+                    .and_then(|name| name.strip_suffix(".json"))
+                else {
+                    continue;
+                };
+                let version: u64 = version.parse().unwrap();
+                versions.push(version);
+
+                let fixture = read_to_string(&path).unwrap();
+                let dir = tempfile::tempdir().unwrap();
+                let file_path = dir.path().join("db.json").to_str().unwrap().to_owned();
+                fs::write(&file_path, &fixture).unwrap();
+                let db_path = JSONFileDatabase::new(file_path);
+
+                let db_state = db_path.read_db().unwrap();
+                assert_eq!(db_state, fixture_state(), "{}", file_name);
+                let _lock = db_path.lock().unwrap();
+                db_path.write_db(&db_state).unwrap();
+
+                let content = read_to_string(&db_path.file_path).unwrap();
+                let saved: serde_json::Value = serde_json::from_str(&content).unwrap();
+                assert_eq!(
+                    saved["schema_version"],
+                    migrations::CURRENT_SCHEMA_VERSION,
+                    "{}",
+                    file_name
+                );
+                let backup = read_to_string(format!("{}.v{}.bak", db_path.file_path, version));
+                if version < migrations::CURRENT_SCHEMA_VERSION {
+                    assert_eq!(backup.unwrap(), fixture, "{}", file_name);
+                } else {
+                    assert!(backup.is_err(), "{}", file_name);
+                }
+                assert_eq!(db_path.read_db().unwrap(), fixture_state(), "{}", file_name);
+            }
+            // every schema version needs a fixture
+            versions.sort();
+            assert_eq!(
+                versions,
+                (0..=migrations::CURRENT_SCHEMA_VERSION).collect::<Vec<_>>()
+            );
+        }
+        #[test]
+        fn read_db_should_back_up_old_versions_without_rewriting_them() {
+            let dir = tempfile::tempdir().unwrap();
+            let file_path = dir.path().join("db.json").to_str().unwrap().to_owned();
+            let fixture = include_str!("../tests/fixtures/db_v0.json");
+            fs::write(&file_path, fixture).unwrap();
+
+            let db_path = JSONFileDatabase::new(file_path);
+            assert_eq!(db_path.read_db().unwrap(), fixture_state());
+            assert_eq!(read_to_string(&db_path.file_path).unwrap(), fixture);
+            let backup_path = format!("{}.v0.bak", db_path.file_path);
+            assert_eq!(read_to_string(&backup_path).unwrap(), fixture);
+
+            // the backup is only taken once per loaded version
+            fs::remove_file(&backup_path).unwrap();
+            db_path.read_db().unwrap();
+            assert!(!Path::new(&backup_path).exists());
+        }
+        #[test]
+        fn read_db_should_fail_for_newer_schema_version() {
+            let dir = tempfile::tempdir().unwrap();
+            let file_path = dir.path().join("db.json").to_str().unwrap().to_owned();
+            let content = format!(
+                r#"{{ "schema_version": {}, "last_item_id": 0, "epics": {{}}, "stories": {{}} }}"#,
+                migrations::CURRENT_SCHEMA_VERSION + 1
+            );
+            fs::write(&file_path, &content).unwrap();
+
+            let db_path = JSONFileDatabase::new(file_path);
+            assert!(db_path.read_db().is_err());
+            assert_eq!(read_to_string(&db_path.file_path).unwrap(), content);
+        }
+        #[test]
+        fn history_should_persist_next_to_database() {
+            let dir = tempfile::tempdir().unwrap();
+            let file_path = dir.path().join("db.json").to_str().unwrap().to_owned();
+            let db = JiraDatabase::new(file_path.clone(), DEFAULT_LOCK_TIMEOUT);
+            db.database
+                .write_db(&DBState {
+                    last_item_id: 0,
+                    epics: HashMap::new(),
+                    stories: HashMap::new(),
+                    users: vec![],
+                })
+                .unwrap();
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+
+            let db = JiraDatabase::new(file_path, DEFAULT_LOCK_TIMEOUT);
+            assert_eq!(db.undo().unwrap(), Some(format!("create epic {}", epic_id)));
+            assert!(db.list_epics().unwrap().is_empty());
+        }
+        #[test]
+        fn changelog_should_persist_next_to_database() {
+            let dir = tempfile::tempdir().unwrap();
+            let file_path = dir.path().join("db.json").to_str().unwrap().to_owned();
+            let db = JiraDatabase::new(file_path.clone(), DEFAULT_LOCK_TIMEOUT);
+            db.database
+                .write_db(&DBState {
+                    last_item_id: 0,
+                    epics: HashMap::new(),
+                    stories: HashMap::new(),
+                    users: vec![],
+                })
+                .unwrap();
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            db.update_epic_priority(epic_id, Priority::High).unwrap();
+
+            let db = JiraDatabase::new(file_path, DEFAULT_LOCK_TIMEOUT);
+            db.delete_epic(epic_id).unwrap();
+            let fields: Vec<String> = db
+                .get_item_history(epic_id)
+                .unwrap()
+                .into_iter()
+                .map(|change| change.field)
+                .collect();
+            assert_eq!(fields, vec!["created", "priority", "deleted"]);
+        }
+        #[test]
+        fn lock_should_fail_while_held_by_someone_else() {
+            let dir = tempfile::tempdir().unwrap();
+            let mut db_path =
+                JSONFileDatabase::new(dir.path().join("db.json").to_str().unwrap().to_owned());
+            db_path.lock_timeout = Duration::from_millis(100);
+
+            let lock = db_path.lock().unwrap();
+            let result = db_path.lock();
+            assert_eq!(
+                result.err().unwrap().to_string(),
+                format!("database is locked by pid {}", process::id())
+            );
+
+            drop(lock);
+            assert!(db_path.lock().is_ok());
+        }
+        #[test]
+        fn jira_database_should_not_modify_locked_database() {
+            let dir = tempfile::tempdir().unwrap();
+            let file_path = dir.path().join("db.json").to_str().unwrap().to_owned();
+            let db = JiraDatabase::new(file_path.clone(), Duration::from_millis(100));
+            db.database
+                .write_db(&DBState {
+                    last_item_id: 0,
+                    epics: HashMap::new(),
+                    stories: HashMap::new(),
+                    users: vec![],
+                })
+                .unwrap();
+
+            let _lock = JSONFileDatabase::new(file_path).lock().unwrap();
+            let result = db.create_epic(Epic::new("".to_owned(), "".to_owned()));
+            assert!(result.is_err());
+            assert!(db.read_db().unwrap().epics.is_empty());
         }
     }
 }
@@ -0,0 +1,206 @@
+use anyhow::{anyhow, Context, Result};
+use serde_json::{json, Value};
+
+/// Upgrade steps of the persisted JSON format, `MIGRATIONS[n]` turns version `n` into `n + 1`.
+const MIGRATIONS: &[fn(&mut Value) -> Result<()>] = &[
+    v0_to_v1, v1_to_v2, v2_to_v3, v3_to_v4, v4_to_v5, v5_to_v6, v6_to_v7,
+];
+
+pub const CURRENT_SCHEMA_VERSION: u64 = MIGRATIONS.len() as u64;
+
+/// Upgrades a serialized database in place to the current schema version step by step.
+/// Returns the version the database had before.
+pub fn migrate(db: &mut Value) -> Result<u64> {
+    if !db.is_object() {
+        return Err(anyhow!("database is not a JSON object"));
+    }
+    let version = match db.get("schema_version") {
+        Some(version) => version
+            .as_u64()
+            .ok_or_else(|| anyhow!("invalid schema_version {}", version))?,
+        None => 0,
+    };
+    if version > CURRENT_SCHEMA_VERSION {
+        return Err(anyhow!(
+            "database schema version {} is newer than the supported version {}",
+            version,
+            CURRENT_SCHEMA_VERSION
+        ));
+    }
+    for (step, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        migration(db).with_context(|| {
+            anyhow!(
+                "failed to migrate database from schema version {} to {}",
+                step,
+                step + 1
+            )
+        })?;
+        db["schema_version"] = json!(step + 1);
+    }
+    Ok(version)
+}
+
+/// Version 0 is the original format without a `schema_version` field.
+fn v0_to_v1(_db: &mut Value) -> Result<()> {
+    Ok(())
+}
+
+/// Version 2 adds a `priority` to every epic and story.
+fn v1_to_v2(db: &mut Value) -> Result<()> {
+    add_item_field(db, "priority", json!("Medium"))
+}
+
+/// Version 3 adds the user registry, epic owners and story assignees are optional.
+fn v2_to_v3(db: &mut Value) -> Result<()> {
+    db.as_object_mut()
+        .ok_or_else(|| anyhow!("database is not a JSON object"))?
+        .entry("users")
+        .or_insert_with(|| json!([]));
+    Ok(())
+}
+
+/// Version 4 adds the `labels` of every epic and story.
+fn v3_to_v4(db: &mut Value) -> Result<()> {
+    add_item_field(db, "labels", json!([]))
+}
+
+/// Version 5 adds the `created` and `updated` times, unknown for items from before.
+fn v4_to_v5(db: &mut Value) -> Result<()> {
+    add_item_field(db, "created", Value::Null)?;
+    add_item_field(db, "updated", Value::Null)
+}
+
+/// Version 6 adds the `comments` of every story.
+fn v5_to_v6(db: &mut Value) -> Result<()> {
+    add_field(db, &["stories"], "comments", json!([]))
+}
+
+/// Version 7 adds the `jira_key` of items imported from Jira, `None` for all others.
+fn v6_to_v7(db: &mut Value) -> Result<()> {
+    add_item_field(db, "jira_key", Value::Null)
+}
+
+/// Sets `field` of every epic and story that does not have it yet to `default`.
+fn add_item_field(db: &mut Value, field: &str, default: Value) -> Result<()> {
+    add_field(db, &["epics", "stories"], field, default)
+}
+
+/// Sets `field` of every item of the given kinds that does not have it yet to `default`.
+fn add_field(db: &mut Value, kinds: &[&str], field: &str, default: Value) -> Result<()> {
+    for kind in kinds {
+        let Some(items) = db.get_mut(kind) else {
+            continue;
+        };
+        let items = items
+            .as_object_mut()
+            .ok_or_else(|| anyhow!("{} is not a JSON object", kind))?;
+        for (id, item) in items.iter_mut() {
+            let item = item
+                .as_object_mut()
+                .ok_or_else(|| anyhow!("{} {} is not a JSON object", kind, id))?;
+            item.entry(field).or_insert_with(|| default.clone());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrate_should_add_schema_version() {
+        let mut db = json!({ "last_item_id": 0, "epics": {}, "stories": {} });
+
+        assert_eq!(migrate(&mut db).unwrap(), 0);
+        assert_eq!(db["schema_version"], CURRENT_SCHEMA_VERSION);
+
+        assert_eq!(migrate(&mut db).unwrap(), CURRENT_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn v1_to_v2_should_add_default_priority() {
+        let mut db = json!({
+            "schema_version": 1,
+            "epics": { "1": { "name": "", "priority": "High" } },
+            "stories": { "2": { "name": "" } }
+        });
+
+        migrate(&mut db).unwrap();
+        assert_eq!(db["epics"]["1"]["priority"], "High");
+        assert_eq!(db["stories"]["2"]["priority"], "Medium");
+    }
+
+    #[test]
+    fn v2_to_v3_should_add_user_registry() {
+        let mut db = json!({ "schema_version": 2, "epics": {}, "stories": {} });
+
+        migrate(&mut db).unwrap();
+        assert_eq!(db["users"], json!([]));
+    }
+
+    #[test]
+    fn v3_to_v4_should_add_empty_labels() {
+        let mut db = json!({
+            "schema_version": 3,
+            "epics": { "1": { "name": "" } },
+            "stories": { "2": { "name": "", "labels": ["ui"] } }
+        });
+
+        migrate(&mut db).unwrap();
+        assert_eq!(db["epics"]["1"]["labels"], json!([]));
+        assert_eq!(db["stories"]["2"]["labels"], json!(["ui"]));
+    }
+
+    #[test]
+    fn v4_to_v5_should_add_unknown_timestamps() {
+        let mut db = json!({
+            "schema_version": 4,
+            "epics": { "1": { "name": "" } },
+            "stories": { "2": { "name": "", "created": "2024-01-01T00:00:00Z" } }
+        });
+
+        migrate(&mut db).unwrap();
+        assert_eq!(db["epics"]["1"]["created"], Value::Null);
+        assert_eq!(db["epics"]["1"]["updated"], Value::Null);
+        assert_eq!(db["stories"]["2"]["created"], "2024-01-01T00:00:00Z");
+    }
+
+    #[test]
+    fn v5_to_v6_should_add_empty_story_comments() {
+        let mut db = json!({
+            "schema_version": 5,
+            "epics": { "1": { "name": "" } },
+            "stories": { "2": { "name": "" } }
+        });
+
+        migrate(&mut db).unwrap();
+        assert_eq!(db["epics"]["1"].get("comments"), None);
+        assert_eq!(db["stories"]["2"]["comments"], json!([]));
+    }
+
+    #[test]
+    fn v6_to_v7_should_add_missing_jira_keys() {
+        let mut db = json!({
+            "schema_version": 6,
+            "epics": { "1": { "name": "" } },
+            "stories": { "2": { "name": "" } }
+        });
+
+        migrate(&mut db).unwrap();
+        assert_eq!(db["epics"]["1"]["jira_key"], Value::Null);
+        assert_eq!(db["stories"]["2"]["jira_key"], Value::Null);
+    }
+
+    #[test]
+    fn migrate_should_reject_newer_and_invalid_versions() {
+        let mut db = json!({ "schema_version": CURRENT_SCHEMA_VERSION + 1 });
+        assert!(migrate(&mut db).is_err());
+
+        let mut db = json!({ "schema_version": "one" });
+        assert!(migrate(&mut db).is_err());
+
+        let mut db = json!([]);
+        assert!(migrate(&mut db).is_err());
+    }
+}
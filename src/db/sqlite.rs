@@ -0,0 +1,906 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    time::Duration,
+};
+
+use anyhow::{anyhow, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use super::{lock_file, Database, DatabaseLock};
+use crate::models::{Comment, DBState, Epic, FieldChange, History, Priority, Status, Story, User};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS meta (
+        key TEXT PRIMARY KEY,
+        value INTEGER NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS epics (
+        id INTEGER PRIMARY KEY,
+        name TEXT NOT NULL,
+        description TEXT NOT NULL,
+        status TEXT NOT NULL,
+        priority TEXT NOT NULL DEFAULT 'Medium',
+        owner TEXT,
+        created TEXT,
+        updated TEXT,
+        jira_key TEXT
+    );
+    CREATE TABLE IF NOT EXISTS stories (
+        id INTEGER PRIMARY KEY,
+        name TEXT NOT NULL,
+        description TEXT NOT NULL,
+        status TEXT NOT NULL,
+        priority TEXT NOT NULL DEFAULT 'Medium',
+        assignee TEXT,
+        created TEXT,
+        updated TEXT,
+        jira_key TEXT
+    );
+    CREATE TABLE IF NOT EXISTS users (
+        username TEXT PRIMARY KEY,
+        name TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS epic_stories (
+        epic_id INTEGER NOT NULL REFERENCES epics(id) ON DELETE CASCADE,
+        story_id INTEGER NOT NULL UNIQUE REFERENCES stories(id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        PRIMARY KEY (epic_id, position)
+    );
+    CREATE TABLE IF NOT EXISTS epic_labels (
+        epic_id INTEGER NOT NULL REFERENCES epics(id) ON DELETE CASCADE,
+        label TEXT NOT NULL,
+        PRIMARY KEY (epic_id, label)
+    );
+    CREATE TABLE IF NOT EXISTS story_labels (
+        story_id INTEGER NOT NULL REFERENCES stories(id) ON DELETE CASCADE,
+        label TEXT NOT NULL,
+        PRIMARY KEY (story_id, label)
+    );
+    CREATE TABLE IF NOT EXISTS story_comments (
+        story_id INTEGER NOT NULL REFERENCES stories(id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        author TEXT,
+        time TEXT NOT NULL,
+        body TEXT NOT NULL,
+        PRIMARY KEY (story_id, position)
+    );
+    CREATE TABLE IF NOT EXISTS history (
+        id INTEGER PRIMARY KEY CHECK (id = 1),
+        data TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS changelog (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        item_id INTEGER NOT NULL,
+        field TEXT NOT NULL,
+        old_value TEXT,
+        new_value TEXT,
+        time TEXT NOT NULL,
+        actor TEXT
+    );
+    CREATE INDEX IF NOT EXISTS changelog_item_id ON changelog (item_id);
+";
+
+pub struct SqliteDatabase {
+    pub file_path: String,
+    pub lock_timeout: Duration,
+    connection: Connection,
+}
+impl SqliteDatabase {
+    pub fn open(file_path: String, lock_timeout: Duration) -> Result<Self> {
+        let connection = Connection::open(&file_path)?;
+        connection.busy_timeout(lock_timeout)?;
+        connection.execute_batch("PRAGMA foreign_keys = ON;")?;
+        connection.execute_batch(SCHEMA)?;
+        // columns added after the first release are missing in older files
+        for table in ["epics", "stories"] {
+            add_column_if_missing(
+                &connection,
+                table,
+                "priority",
+                "TEXT NOT NULL DEFAULT 'Medium'",
+            )?;
+        }
+        add_column_if_missing(&connection, "epics", "owner", "TEXT")?;
+        add_column_if_missing(&connection, "stories", "assignee", "TEXT")?;
+        for table in ["epics", "stories"] {
+            add_column_if_missing(&connection, table, "created", "TEXT")?;
+            add_column_if_missing(&connection, table, "updated", "TEXT")?;
+            add_column_if_missing(&connection, table, "jira_key", "TEXT")?;
+        }
+        Ok(Self {
+            file_path,
+            lock_timeout,
+            connection,
+        })
+    }
+}
+fn add_column_if_missing(
+    connection: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<()> {
+    let exists: bool = connection.query_row(
+        "SELECT EXISTS (SELECT 1 FROM pragma_table_info(?1) WHERE name = ?2)",
+        params![table, column],
+        |row| row.get(0),
+    )?;
+    if !exists {
+        connection.execute_batch(&format!(
+            "ALTER TABLE {} ADD COLUMN {} {}",
+            table, column, definition
+        ))?;
+    }
+    Ok(())
+}
+fn parse_status(status: &str) -> Result<Status> {
+    status.parse::<Status>().map_err(|error| anyhow!(error))
+}
+fn status_to_sql(status: &Status) -> String {
+    format!("{:?}", status)
+}
+fn parse_priority(priority: &str) -> Result<Priority> {
+    priority.parse::<Priority>().map_err(|error| anyhow!(error))
+}
+fn priority_to_sql(priority: &Priority) -> String {
+    format!("{:?}", priority)
+}
+fn last_item_id(connection: &Connection) -> Result<u32> {
+    Ok(connection
+        .query_row(
+            "SELECT value FROM meta WHERE key = 'last_item_id'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?
+        .unwrap_or(0))
+}
+fn next_item_id(connection: &Connection) -> Result<u32> {
+    let id = last_item_id(connection)? + 1;
+    connection.execute(
+        "INSERT OR REPLACE INTO meta (key, value) VALUES ('last_item_id', ?1)",
+        params![id],
+    )?;
+    Ok(id)
+}
+/// Epics with their story ids, all of them or only the one with id `epic_id`.
+fn query_epics(connection: &Connection, epic_id: Option<u32>) -> Result<HashMap<u32, Epic>> {
+    let mut epics = HashMap::new();
+    let mut statement = connection.prepare(
+        "SELECT id, name, description, status, priority, owner, created, updated, jira_key \
+         FROM epics \
+         WHERE ?1 IS NULL OR id = ?1",
+    )?;
+    let rows = statement.query_map(params![epic_id], |row| {
+        let mut epic = Epic::new(row.get(1)?, row.get(2)?);
+        epic.owner = row.get(5)?;
+        epic.created = row.get(6)?;
+        epic.updated = row.get(7)?;
+        epic.jira_key = row.get(8)?;
+        Ok((
+            row.get::<_, u32>(0)?,
+            epic,
+            row.get::<_, String>(3)?,
+            row.get::<_, String>(4)?,
+        ))
+    })?;
+    for row in rows {
+        let (id, mut epic, status, priority) = row?;
+        epic.status = parse_status(&status)?;
+        epic.priority = parse_priority(&priority)?;
+        epics.insert(id, epic);
+    }
+
+    let mut statement = connection.prepare(
+        "SELECT epic_id, story_id FROM epic_stories WHERE ?1 IS NULL OR epic_id = ?1 \
+         ORDER BY epic_id, position",
+    )?;
+    let rows = statement.query_map(params![epic_id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    for row in rows {
+        let (epic_id, story_id): (u32, u32) = row?;
+        epics
+            .get_mut(&epic_id)
+            .ok_or_else(|| anyhow!("could not find epic {} of story {}", epic_id, story_id))?
+            .stories
+            .push(story_id);
+    }
+    for (epic_id, label) in query_labels(connection, "epic", epic_id)? {
+        if let Some(epic) = epics.get_mut(&epic_id) {
+            epic.labels.insert(label);
+        }
+    }
+    Ok(epics)
+}
+/// Stories, all of them or only the one with id `story_id`.
+fn query_stories(connection: &Connection, story_id: Option<u32>) -> Result<HashMap<u32, Story>> {
+    let mut stories = HashMap::new();
+    let mut statement = connection.prepare(
+        "SELECT id, name, description, status, priority, assignee, created, updated, jira_key \
+         FROM stories \
+         WHERE ?1 IS NULL OR id = ?1",
+    )?;
+    let rows = statement.query_map(params![story_id], |row| {
+        let mut story = Story::new(row.get(1)?, row.get(2)?);
+        story.assignee = row.get(5)?;
+        story.created = row.get(6)?;
+        story.updated = row.get(7)?;
+        story.jira_key = row.get(8)?;
+        Ok((
+            row.get::<_, u32>(0)?,
+            story,
+            row.get::<_, String>(3)?,
+            row.get::<_, String>(4)?,
+        ))
+    })?;
+    for row in rows {
+        let (id, mut story, status, priority) = row?;
+        story.status = parse_status(&status)?;
+        story.priority = parse_priority(&priority)?;
+        stories.insert(id, story);
+    }
+    for (story_id, label) in query_labels(connection, "story", story_id)? {
+        if let Some(story) = stories.get_mut(&story_id) {
+            story.labels.insert(label);
+        }
+    }
+    let mut statement = connection.prepare(
+        "SELECT story_id, author, time, body FROM story_comments \
+         WHERE ?1 IS NULL OR story_id = ?1 ORDER BY story_id, position",
+    )?;
+    let rows = statement.query_map(params![story_id], |row| {
+        Ok((
+            row.get::<_, u32>(0)?,
+            Comment {
+                author: row.get(1)?,
+                time: row.get(2)?,
+                body: row.get(3)?,
+            },
+        ))
+    })?;
+    for row in rows {
+        let (story_id, comment) = row?;
+        if let Some(story) = stories.get_mut(&story_id) {
+            story.comments.push(comment);
+        }
+    }
+    Ok(stories)
+}
+/// Replaces the comments of one story in the `story_comments` table.
+fn replace_comments(connection: &Connection, story_id: u32, comments: &[Comment]) -> Result<()> {
+    connection.execute(
+        "DELETE FROM story_comments WHERE story_id = ?1",
+        params![story_id],
+    )?;
+    for (position, comment) in (0u32..).zip(comments.iter()) {
+        connection.execute(
+            "INSERT INTO story_comments (story_id, position, author, time, body) \
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                story_id,
+                position,
+                comment.author,
+                comment.time,
+                comment.body
+            ],
+        )?;
+    }
+    Ok(())
+}
+/// `(item id, label)` rows of the `<kind>_labels` table, for all items or only the one with `id`.
+fn query_labels(
+    connection: &Connection,
+    kind: &str,
+    id: Option<u32>,
+) -> Result<Vec<(u32, String)>> {
+    let mut statement = connection.prepare(&format!(
+        "SELECT {kind}_id, label FROM {kind}_labels WHERE ?1 IS NULL OR {kind}_id = ?1"
+    ))?;
+    let labels = statement
+        .query_map(params![id], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<rusqlite::Result<Vec<(u32, String)>>>()?;
+    Ok(labels)
+}
+/// Replaces the labels of one item in the `<kind>_labels` table.
+fn replace_labels(
+    connection: &Connection,
+    kind: &str,
+    id: u32,
+    labels: &BTreeSet<String>,
+) -> Result<()> {
+    connection.execute(
+        &format!("DELETE FROM {kind}_labels WHERE {kind}_id = ?1"),
+        params![id],
+    )?;
+    for label in labels {
+        connection.execute(
+            &format!("INSERT INTO {kind}_labels ({kind}_id, label) VALUES (?1, ?2)"),
+            params![id, label],
+        )?;
+    }
+    Ok(())
+}
+fn query_users(connection: &Connection) -> Result<Vec<User>> {
+    let mut statement = connection.prepare("SELECT username, name FROM users ORDER BY rowid")?;
+    let users = statement
+        .query_map([], |row| Ok(User::new(row.get(0)?, row.get(1)?)))?
+        .collect::<rusqlite::Result<Vec<User>>>()?;
+    Ok(users)
+}
+fn insert_user_row(connection: &Connection, user: &User) -> Result<()> {
+    connection.execute(
+        "INSERT INTO users (username, name) VALUES (?1, ?2)",
+        params![user.username, user.name],
+    )?;
+    Ok(())
+}
+fn insert_epic_row(connection: &Connection, epic_id: u32, epic: &Epic) -> Result<()> {
+    connection.execute(
+        "INSERT INTO epics (id, name, description, status, priority, owner, created, updated, \
+         jira_key) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            epic_id,
+            epic.name,
+            epic.description,
+            status_to_sql(&epic.status),
+            priority_to_sql(&epic.priority),
+            epic.owner,
+            epic.created,
+            epic.updated,
+            epic.jira_key
+        ],
+    )?;
+    replace_labels(connection, "epic", epic_id, &epic.labels)?;
+    insert_epic_stories(connection, epic_id, &epic.stories)
+}
+fn insert_epic_stories(connection: &Connection, epic_id: u32, story_ids: &[u32]) -> Result<()> {
+    for (position, story_id) in (0u32..).zip(story_ids.iter()) {
+        connection.execute(
+            "INSERT INTO epic_stories (epic_id, story_id, position) VALUES (?1, ?2, ?3)",
+            params![epic_id, story_id, position],
+        )?;
+    }
+    Ok(())
+}
+fn insert_story_row(connection: &Connection, story_id: u32, story: &Story) -> Result<()> {
+    connection.execute(
+        "INSERT INTO stories (id, name, description, status, priority, assignee, created, \
+         updated, jira_key) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            story_id,
+            story.name,
+            story.description,
+            status_to_sql(&story.status),
+            priority_to_sql(&story.priority),
+            story.assignee,
+            story.created,
+            story.updated,
+            story.jira_key
+        ],
+    )?;
+    replace_labels(connection, "story", story_id, &story.labels)?;
+    replace_comments(connection, story_id, &story.comments)
+}
+impl Database for SqliteDatabase {
+    fn read_db(&self) -> Result<DBState> {
+        // a transaction gives a consistent snapshot across the separate queries
+        let tx = self.connection.unchecked_transaction()?;
+        Ok(DBState {
+            last_item_id: last_item_id(&tx)?,
+            epics: query_epics(&tx, None)?,
+            stories: query_stories(&tx, None)?,
+            users: query_users(&tx)?,
+        })
+    }
+    fn write_db(&self, db_state: &DBState) -> Result<()> {
+        let tx = self.connection.unchecked_transaction()?;
+        tx.execute_batch(
+            "DELETE FROM epic_stories; DELETE FROM stories; DELETE FROM epics; DELETE FROM users;",
+        )?;
+        tx.execute(
+            "INSERT OR REPLACE INTO meta (key, value) VALUES ('last_item_id', ?1)",
+            params![db_state.last_item_id],
+        )?;
+        for (id, story) in &db_state.stories {
+            insert_story_row(&tx, *id, story)?;
+        }
+        for (id, epic) in &db_state.epics {
+            insert_epic_row(&tx, *id, epic)?;
+        }
+        for user in &db_state.users {
+            insert_user_row(&tx, user)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+    fn lock(&self) -> Result<DatabaseLock> {
+        lock_file(&self.file_path, self.lock_timeout)
+    }
+    fn read_history(&self) -> Result<History> {
+        let data: Option<String> = self
+            .connection
+            .query_row("SELECT data FROM history WHERE id = 1", [], |row| {
+                row.get(0)
+            })
+            .optional()?;
+        match data {
+            Some(data) => Ok(serde_json::from_str(&data)?),
+            None => Ok(History::default()),
+        }
+    }
+    fn write_history(&self, history: &History) -> Result<()> {
+        self.connection.execute(
+            "INSERT OR REPLACE INTO history (id, data) VALUES (1, ?1)",
+            params![serde_json::to_string(history)?],
+        )?;
+        Ok(())
+    }
+    fn read_changelog(&self, item_id: u32) -> Result<Vec<FieldChange>> {
+        let mut statement = self.connection.prepare(
+            "SELECT item_id, field, old_value, new_value, time, actor FROM changelog \
+             WHERE item_id = ?1 ORDER BY id",
+        )?;
+        let entries = statement
+            .query_map(params![item_id], |row| {
+                Ok(FieldChange {
+                    item_id: row.get(0)?,
+                    field: row.get(1)?,
+                    old_value: row.get(2)?,
+                    new_value: row.get(3)?,
+                    time: row.get(4)?,
+                    actor: row.get(5)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<FieldChange>>>()?;
+        Ok(entries)
+    }
+    fn append_changelog(&self, entries: &[FieldChange]) -> Result<()> {
+        let tx = self.connection.unchecked_transaction()?;
+        for entry in entries {
+            tx.execute(
+                "INSERT INTO changelog (item_id, field, old_value, new_value, time, actor) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    entry.item_id,
+                    entry.field,
+                    entry.old_value,
+                    entry.new_value,
+                    entry.time,
+                    entry.actor
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn get_epic(&self, epic_id: u32) -> Result<Option<Epic>> {
+        let tx = self.connection.unchecked_transaction()?;
+        Ok(query_epics(&tx, Some(epic_id))?.remove(&epic_id))
+    }
+    fn get_story(&self, story_id: u32) -> Result<Option<Story>> {
+        let tx = self.connection.unchecked_transaction()?;
+        Ok(query_stories(&tx, Some(story_id))?.remove(&story_id))
+    }
+    fn list_epics(&self) -> Result<HashMap<u32, Epic>> {
+        let tx = self.connection.unchecked_transaction()?;
+        query_epics(&tx, None)
+    }
+    fn list_stories(&self) -> Result<HashMap<u32, Story>> {
+        let tx = self.connection.unchecked_transaction()?;
+        query_stories(&tx, None)
+    }
+    fn list_users(&self) -> Result<Vec<User>> {
+        query_users(&self.connection)
+    }
+    fn insert_user(&self, user: User) -> Result<()> {
+        let exists: bool = self.connection.query_row(
+            "SELECT EXISTS (SELECT 1 FROM users WHERE username = ?1)",
+            params![user.username],
+            |row| row.get(0),
+        )?;
+        if exists {
+            return Err(anyhow!("user {} already exists", user.username));
+        }
+        insert_user_row(&self.connection, &user)
+    }
+    fn delete_user(&self, username: &str) -> Result<()> {
+        let deleted = self
+            .connection
+            .execute("DELETE FROM users WHERE username = ?1", params![username])?;
+        if deleted == 0 {
+            return Err(anyhow!("could not find user {} in database", username));
+        }
+        Ok(())
+    }
+
+    fn insert_epic(&self, epic: Epic) -> Result<u32> {
+        let tx = self.connection.unchecked_transaction()?;
+        let epic_id = next_item_id(&tx)?;
+        insert_epic_row(&tx, epic_id, &epic)?;
+        tx.commit()?;
+        Ok(epic_id)
+    }
+    fn insert_story(&self, story: Story, epic_id: u32) -> Result<u32> {
+        let tx = self.connection.unchecked_transaction()?;
+        if query_epics(&tx, Some(epic_id))?.is_empty() {
+            return Err(anyhow!("could not find epic in database for create story"));
+        }
+        let story_id = next_item_id(&tx)?;
+        insert_story_row(&tx, story_id, &story)?;
+        tx.execute(
+            "INSERT INTO epic_stories (epic_id, story_id, position) \
+             SELECT ?1, ?2, COALESCE(MAX(position) + 1, 0) FROM epic_stories WHERE epic_id = ?1",
+            params![epic_id, story_id],
+        )?;
+        tx.commit()?;
+        Ok(story_id)
+    }
+
+    fn update_epic(&self, epic_id: u32, epic: Epic) -> Result<()> {
+        let tx = self.connection.unchecked_transaction()?;
+        let updated = tx.execute(
+            "UPDATE epics SET name = ?2, description = ?3, status = ?4, priority = ?5, owner = ?6, \
+             created = ?7, updated = ?8, jira_key = ?9 WHERE id = ?1",
+            params![
+                epic_id,
+                epic.name,
+                epic.description,
+                status_to_sql(&epic.status),
+                priority_to_sql(&epic.priority),
+                epic.owner,
+                epic.created,
+                epic.updated,
+                epic.jira_key
+            ],
+        )?;
+        if updated == 0 {
+            return Err(anyhow!(
+                "could not find epic {} in database for update",
+                epic_id
+            ));
+        }
+        replace_labels(&tx, "epic", epic_id, &epic.labels)?;
+        tx.execute(
+            "DELETE FROM epic_stories WHERE epic_id = ?1",
+            params![epic_id],
+        )?;
+        insert_epic_stories(&tx, epic_id, &epic.stories)?;
+        tx.commit()?;
+        Ok(())
+    }
+    fn update_story(&self, story_id: u32, story: Story) -> Result<()> {
+        let tx = self.connection.unchecked_transaction()?;
+        let updated = tx.execute(
+            "UPDATE stories SET name = ?2, description = ?3, status = ?4, priority = ?5, \
+             assignee = ?6, created = ?7, updated = ?8, jira_key = ?9 WHERE id = ?1",
+            params![
+                story_id,
+                story.name,
+                story.description,
+                status_to_sql(&story.status),
+                priority_to_sql(&story.priority),
+                story.assignee,
+                story.created,
+                story.updated,
+                story.jira_key
+            ],
+        )?;
+        if updated == 0 {
+            return Err(anyhow!(
+                "could not find story {} in database for update",
+                story_id
+            ));
+        }
+        replace_labels(&tx, "story", story_id, &story.labels)?;
+        replace_comments(&tx, story_id, &story.comments)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn move_story(&self, story_id: u32, from_epic_id: u32, to_epic_id: u32) -> Result<()> {
+        if from_epic_id == to_epic_id {
+            return Err(anyhow!(
+                "story {} is already in epic {}",
+                story_id,
+                to_epic_id
+            ));
+        }
+        let tx = self.connection.unchecked_transaction()?;
+        if query_epics(&tx, Some(to_epic_id))?.is_empty() {
+            return Err(anyhow!("could not find epic in database for move story"));
+        }
+        let from_epic = query_epics(&tx, Some(from_epic_id))?
+            .remove(&from_epic_id)
+            .ok_or_else(|| anyhow!("could not find epic in database for move story"))?;
+        if !from_epic.stories.contains(&story_id) {
+            return Err(anyhow!(
+                "could not find story_id in epic stories for move story"
+            ));
+        }
+        tx.execute(
+            "UPDATE epic_stories SET epic_id = ?2, position = \
+             (SELECT COALESCE(MAX(position) + 1, 0) FROM epic_stories WHERE epic_id = ?2) \
+             WHERE story_id = ?1",
+            params![story_id, to_epic_id],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+    fn delete_epic(&self, epic_id: u32) -> Result<()> {
+        let tx = self.connection.unchecked_transaction()?;
+        if query_epics(&tx, Some(epic_id))?.is_empty() {
+            return Err(anyhow!("could not find epic in database for delete epic"));
+        }
+        tx.execute(
+            "DELETE FROM stories WHERE id IN (SELECT story_id FROM epic_stories WHERE epic_id = ?1)",
+            params![epic_id],
+        )?;
+        tx.execute("DELETE FROM epics WHERE id = ?1", params![epic_id])?;
+        tx.commit()?;
+        Ok(())
+    }
+    fn delete_story(&self, epic_id: u32, story_id: u32) -> Result<()> {
+        let tx = self.connection.unchecked_transaction()?;
+        let epic = query_epics(&tx, Some(epic_id))?
+            .remove(&epic_id)
+            .ok_or_else(|| anyhow!("could not find epic in db for delete story"))?;
+        if !epic.stories.contains(&story_id) {
+            return Err(anyhow!(
+                "could not find story_id in epic stories for delete story"
+            ));
+        }
+        // the epic_stories row goes with the story through ON DELETE CASCADE
+        tx.execute("DELETE FROM stories WHERE id = ?1", params![story_id])?;
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{JSONFileDatabase, JiraDatabase, DEFAULT_LOCK_TIMEOUT};
+    use chrono::Utc;
+
+    fn open_temp_db(dir: &tempfile::TempDir) -> SqliteDatabase {
+        let file_path = dir.path().join("db.sqlite").to_str().unwrap().to_owned();
+        SqliteDatabase::open(file_path, DEFAULT_LOCK_TIMEOUT).unwrap()
+    }
+
+    #[test]
+    fn read_db_should_return_empty_state_for_new_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = open_temp_db(&dir);
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.last_item_id, 0);
+        assert!(db_state.epics.is_empty());
+        assert!(db_state.stories.is_empty());
+    }
+
+    #[test]
+    fn write_db_should_round_trip_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = open_temp_db(&dir);
+
+        let mut epic = Epic::new("epic".to_owned(), "epic description".to_owned());
+        epic.status = Status::InProgress;
+        epic.priority = Priority::Low;
+        epic.stories = vec![4, 2];
+        let mut story = Story::new("story".to_owned(), "story description".to_owned());
+        story.status = Status::Resolved;
+        story.priority = Priority::Highest;
+        story.assignee = Some("alice".to_owned());
+        epic.owner = Some("bob".to_owned());
+        epic.labels.insert("frontend".to_owned());
+        epic.created = Some(Utc::now());
+        story.updated = Some(Utc::now());
+        story.comments.push(Comment {
+            author: Some("bob".to_owned()),
+            time: Utc::now(),
+            body: "first\nsecond line".to_owned(),
+        });
+        story
+            .labels
+            .extend(["tech-debt".to_owned(), "customer-x".to_owned()]);
+        let db_state = DBState {
+            last_item_id: 4,
+            epics: HashMap::from([(1, epic)]),
+            stories: HashMap::from([
+                (2, story),
+                (4, Story::new("other".to_owned(), "".to_owned())),
+            ]),
+            users: vec![
+                User::new("bob".to_owned(), "Bob".to_owned()),
+                User::new("alice".to_owned(), "Alice".to_owned()),
+            ],
+        };
+
+        db.write_db(&db_state).unwrap();
+        assert_eq!(db.read_db().unwrap(), db_state);
+
+        // reopening reads the same state from disk
+        let db = SqliteDatabase::open(db.file_path.clone(), DEFAULT_LOCK_TIMEOUT).unwrap();
+        assert_eq!(db.read_db().unwrap(), db_state);
+    }
+
+    #[test]
+    fn jira_database_should_work_on_sqlite() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = JiraDatabase::with_database(Box::new(open_temp_db(&dir)));
+
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        db.update_story_status(story_id, Status::Closed).unwrap();
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.epics[&epic_id].stories, vec![story_id]);
+        assert_eq!(db_state.stories[&story_id].status, Status::Closed);
+
+        db.delete_epic(epic_id).unwrap();
+        let db_state = db.read_db().unwrap();
+        assert!(db_state.epics.is_empty());
+        assert!(db_state.stories.is_empty());
+    }
+
+    #[test]
+    fn item_operations_should_only_touch_their_item() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = open_temp_db(&dir);
+
+        let epic_id = db
+            .insert_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_1 = db
+            .insert_story(Story::new("first".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        let story_2 = db
+            .insert_story(Story::new("second".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        assert!(db
+            .insert_story(Story::new("".to_owned(), "".to_owned()), 999)
+            .is_err());
+
+        let epic = db.get_epic(epic_id).unwrap().unwrap();
+        assert_eq!(epic.stories, vec![story_1, story_2]);
+        assert_eq!(db.get_story(story_2).unwrap().unwrap().name, "second");
+        assert_eq!(db.get_story(999).unwrap(), None);
+
+        let mut story = db.get_story(story_1).unwrap().unwrap();
+        story.status = Status::InProgress;
+        story.labels.insert("backend".to_owned());
+        db.update_story(story_1, story.clone()).unwrap();
+        assert_eq!(db.get_story(story_1).unwrap(), Some(story));
+        assert!(db
+            .update_story(999, Story::new("".to_owned(), "".to_owned()))
+            .is_err());
+
+        assert!(db.delete_story(epic_id, 999).is_err());
+        db.delete_story(epic_id, story_1).unwrap();
+        assert_eq!(
+            db.get_epic(epic_id).unwrap().unwrap().stories,
+            vec![story_2]
+        );
+        assert_eq!(db.list_stories().unwrap().len(), 1);
+
+        // a new story after a delete goes to the end of the epic
+        let story_3 = db
+            .insert_story(Story::new("third".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+        assert_eq!(
+            db.get_epic(epic_id).unwrap().unwrap().stories,
+            vec![story_2, story_3]
+        );
+        assert_eq!(db.read_db().unwrap().last_item_id, story_3);
+    }
+
+    #[test]
+    fn history_should_persist_in_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = JiraDatabase::with_database(Box::new(open_temp_db(&dir)));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+
+        let db = JiraDatabase::with_database(Box::new(open_temp_db(&dir)));
+        assert_eq!(db.undo().unwrap(), Some(format!("create epic {}", epic_id)));
+        assert!(db.list_epics().unwrap().is_empty());
+        assert_eq!(db.redo().unwrap(), Some(format!("create epic {}", epic_id)));
+    }
+
+    #[test]
+    fn changelog_should_persist_in_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = JiraDatabase {
+            actor: Some("alice".to_owned()),
+            ..JiraDatabase::with_database(Box::new(open_temp_db(&dir)))
+        };
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        db.update_epic_status(epic_id, Status::Resolved).unwrap();
+
+        let db = JiraDatabase::with_database(Box::new(open_temp_db(&dir)));
+        let history = db.get_item_history(epic_id).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].field, "created");
+        assert_eq!(history[1].field, "status");
+        assert_eq!(history[1].old_value.as_deref(), Some("\"Open\""));
+        assert_eq!(history[1].new_value.as_deref(), Some("\"Resolved\""));
+        assert_eq!(history[1].actor.as_deref(), Some("alice"));
+        assert!(db.get_item_history(999).unwrap().is_empty());
+    }
+
+    #[test]
+    fn move_story_should_append_story_to_target_epic() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = open_temp_db(&dir);
+        let epic_1 = db
+            .insert_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let epic_2 = db
+            .insert_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_1 = db
+            .insert_story(Story::new("".to_owned(), "".to_owned()), epic_1)
+            .unwrap();
+        let story_2 = db
+            .insert_story(Story::new("".to_owned(), "".to_owned()), epic_2)
+            .unwrap();
+
+        assert!(db.move_story(story_1, epic_2, epic_1).is_err());
+        db.move_story(story_1, epic_1, epic_2).unwrap();
+
+        let epics = db.list_epics().unwrap();
+        assert!(epics[&epic_1].stories.is_empty());
+        assert_eq!(epics[&epic_2].stories, vec![story_2, story_1]);
+    }
+
+    #[test]
+    fn copy_database_should_migrate_json_losslessly() {
+        let dir = tempfile::tempdir().unwrap();
+        let json_path = dir.path().join("db.json").to_str().unwrap().to_owned();
+        let json = JiraDatabase::new(json_path.clone(), DEFAULT_LOCK_TIMEOUT);
+        json.database
+            .write_db(&DBState {
+                last_item_id: 0,
+                epics: HashMap::new(),
+                stories: HashMap::new(),
+                users: vec![],
+            })
+            .unwrap();
+        let epic_id = json
+            .create_epic(Epic::new("epic".to_owned(), "".to_owned()))
+            .unwrap();
+        json.create_story(Story::new("story".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let sqlite = open_temp_db(&dir);
+        let copied =
+            crate::db::copy_database(&JSONFileDatabase::new(json_path.clone()), &sqlite).unwrap();
+
+        assert_eq!(copied, json.read_db().unwrap());
+        assert_eq!(sqlite.read_db().unwrap(), copied);
+        assert_eq!(
+            sqlite.read_history().unwrap(),
+            json.database.read_history().unwrap()
+        );
+        assert_eq!(sqlite.read_history().unwrap().undo.len(), 2);
+        for item_id in [epic_id, epic_id + 1] {
+            let changelog = json.database.read_changelog(item_id).unwrap();
+            assert!(!changelog.is_empty());
+            assert_eq!(sqlite.read_changelog(item_id).unwrap(), changelog);
+        }
+
+        // copying again must not duplicate the change log
+        crate::db::copy_database(&JSONFileDatabase::new(json_path), &sqlite).unwrap();
+        assert_eq!(
+            sqlite.read_changelog(epic_id).unwrap(),
+            json.database.read_changelog(epic_id).unwrap()
+        );
+    }
+}
@@ -0,0 +1,324 @@
+use std::fmt::{Display, Formatter};
+
+use crate::models::{Epic, ItemKind, Priority, Status, Story};
+
+/// Filter expression like `status:InProgress epic:2 name~"login"`, every term has to match.
+///
+/// A term is `field:value` (equals, ignoring case) or `field~value` (contains, ignoring case),
+/// a leading `-` negates it. Values with spaces are put in double quotes, `\"` escapes a quote.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    source: String,
+    terms: Vec<Term>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Term {
+    negated: bool,
+    field: Field,
+    contains: bool,
+    value: Value,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Id,
+    Epic,
+    Name,
+    Description,
+    Status,
+    Priority,
+    Assignee,
+    Owner,
+    Label,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Value {
+    Id(u32),
+    Status(Status),
+    Priority(Priority),
+    /// Lowercase text, compared ignoring case.
+    Text(String),
+}
+
+/// The fields of an epic or story that terms are evaluated against.
+struct Fields<'a> {
+    id: u32,
+    epic_id: u32,
+    name: &'a str,
+    description: &'a str,
+    status: &'a Status,
+    priority: Priority,
+    user: Option<&'a str>,
+    labels: Vec<&'a str>,
+}
+
+impl Filter {
+    /// Parses an expression for items of the given kind, errors name the column of the problem.
+    pub fn parse(input: &str, kind: ItemKind) -> Result<Self, String> {
+        let chars: Vec<char> = input.chars().collect();
+        let mut terms = vec![];
+        let mut pos = 0;
+        loop {
+            while pos < chars.len() && chars[pos].is_whitespace() {
+                pos += 1;
+            }
+            if pos == chars.len() {
+                break;
+            }
+            terms.push(parse_term(&chars, &mut pos, kind)?);
+        }
+        if terms.is_empty() {
+            return Err("empty filter expression".to_owned());
+        }
+        Ok(Self {
+            source: input.trim().to_owned(),
+            terms,
+        })
+    }
+    pub fn matches_epic(&self, epic_id: u32, epic: &Epic) -> bool {
+        self.matches(&Fields {
+            id: epic_id,
+            epic_id,
+            name: &epic.name,
+            description: &epic.description,
+            status: &epic.status,
+            priority: epic.priority,
+            user: epic.owner.as_deref(),
+            labels: epic.labels.iter().map(String::as_str).collect(),
+        })
+    }
+    pub fn matches_story(&self, story_id: u32, epic_id: u32, story: &Story) -> bool {
+        self.matches(&Fields {
+            id: story_id,
+            epic_id,
+            name: &story.name,
+            description: &story.description,
+            status: &story.status,
+            priority: story.priority,
+            user: story.assignee.as_deref(),
+            labels: story.labels.iter().map(String::as_str).collect(),
+        })
+    }
+    fn matches(&self, fields: &Fields) -> bool {
+        self.terms
+            .iter()
+            .all(|term| term.matches(fields) != term.negated)
+    }
+}
+
+impl Display for Filter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+impl Term {
+    fn matches(&self, fields: &Fields) -> bool {
+        let text = |value: &str| match &self.value {
+            Value::Text(expected) if self.contains => value.to_lowercase().contains(expected),
+            Value::Text(expected) => value.to_lowercase() == *expected,
+            _ => false,
+        };
+        match (&self.field, &self.value) {
+            (Field::Id, Value::Id(id)) => fields.id == *id,
+            (Field::Epic, Value::Id(id)) => fields.epic_id == *id,
+            (Field::Status, Value::Status(status)) => fields.status == status,
+            (Field::Priority, Value::Priority(priority)) => fields.priority == *priority,
+            (Field::Name, _) => text(fields.name),
+            (Field::Description, _) => text(fields.description),
+            (Field::Assignee | Field::Owner, _) => fields.user.is_some_and(text),
+            (Field::Label, _) => fields.labels.iter().any(|label| text(label)),
+            _ => false,
+        }
+    }
+}
+
+fn error_at(pos: usize, message: String) -> String {
+    format!("{} at column {}", message, pos + 1)
+}
+
+fn parse_term(chars: &[char], pos: &mut usize, kind: ItemKind) -> Result<Term, String> {
+    let negated = chars[*pos] == '-';
+    if negated {
+        *pos += 1;
+    }
+    let start = *pos;
+    while *pos < chars.len() && (chars[*pos].is_alphanumeric() || chars[*pos] == '_') {
+        *pos += 1;
+    }
+    let name: String = chars[start..*pos].iter().collect();
+    if name.is_empty() {
+        return Err(error_at(start, "expected a field name".to_owned()));
+    }
+    let field = parse_field(&name, kind).map_err(|message| error_at(start, message))?;
+    let contains = match chars.get(*pos) {
+        Some(':') => false,
+        Some('~') => true,
+        _ => {
+            return Err(error_at(
+                *pos,
+                format!("expected ':' or '~' after '{}'", name),
+            ))
+        }
+    };
+    *pos += 1;
+    let value_start = *pos;
+    let value = parse_value(chars, pos)?;
+    if value.is_empty() {
+        return Err(error_at(
+            value_start,
+            format!("missing value for '{}'", name),
+        ));
+    }
+    let value = match field {
+        Field::Id | Field::Epic | Field::Status | Field::Priority if contains => {
+            return Err(error_at(
+                start,
+                format!("'~' is not supported for '{}', use ':'", name),
+            ))
+        }
+        Field::Id | Field::Epic => value
+            .parse::<u32>()
+            .map(Value::Id)
+            .map_err(|_| format!("invalid id '{}'", value)),
+        Field::Status => value.parse::<Status>().map(Value::Status),
+        Field::Priority => value.parse::<Priority>().map(Value::Priority),
+        _ => Ok(Value::Text(value.to_lowercase())),
+    }
+    .map_err(|message| error_at(value_start, message))?;
+    Ok(Term {
+        negated,
+        field,
+        contains,
+        value,
+    })
+}
+
+fn parse_field(name: &str, kind: ItemKind) -> Result<Field, String> {
+    let field = match name.to_lowercase().as_str() {
+        "id" => Field::Id,
+        "epic" => Field::Epic,
+        "name" => Field::Name,
+        "description" | "desc" => Field::Description,
+        "status" => Field::Status,
+        "priority" => Field::Priority,
+        "assignee" => Field::Assignee,
+        "owner" => Field::Owner,
+        "label" | "labels" => Field::Label,
+        _ => return Err(format!("unknown field '{}'", name)),
+    };
+    match (field, kind) {
+        (Field::Epic | Field::Assignee, ItemKind::Epic) => {
+            Err(format!("field '{}' only applies to stories", name))
+        }
+        (Field::Owner, ItemKind::Story) => Err(format!("field '{}' only applies to epics", name)),
+        _ => Ok(field),
+    }
+}
+
+/// Reads a bare word or a double-quoted string.
+fn parse_value(chars: &[char], pos: &mut usize) -> Result<String, String> {
+    let mut value = String::new();
+    if chars.get(*pos) != Some(&'"') {
+        while *pos < chars.len() && !chars[*pos].is_whitespace() {
+            value.push(chars[*pos]);
+            *pos += 1;
+        }
+        return Ok(value);
+    }
+    let quote = *pos;
+    *pos += 1;
+    loop {
+        match chars.get(*pos) {
+            None => return Err(error_at(quote, "unterminated quote".to_owned())),
+            Some('"') => {
+                *pos += 1;
+                return Ok(value);
+            }
+            Some('\\') if chars.get(*pos + 1) == Some(&'"') => {
+                value.push('"');
+                *pos += 2;
+            }
+            Some(c) => {
+                value.push(*c);
+                *pos += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn story() -> Story {
+        let mut story = Story::new("Login form".to_owned(), "Email and password".to_owned());
+        story.status = Status::InProgress;
+        story.priority = Priority::High;
+        story.assignee = Some("alice".to_owned());
+        story.labels.insert("frontend".to_owned());
+        story
+    }
+
+    fn matches(input: &str) -> bool {
+        Filter::parse(input, ItemKind::Story)
+            .unwrap()
+            .matches_story(3, 2, &story())
+    }
+
+    #[test]
+    fn terms_should_all_have_to_match() {
+        assert!(matches(r#"status:InProgress epic:2 name~"login""#));
+        assert!(matches("status:in-progress priority:high id:3"));
+        assert!(matches("assignee:Alice label:frontend description~PASS"));
+        assert!(matches(r#"name:"login form""#));
+        assert!(!matches("status:InProgress epic:1"));
+        assert!(!matches("name:login"));
+        assert!(!matches("label~back"));
+    }
+
+    #[test]
+    fn negated_terms_should_exclude_matches() {
+        assert!(matches("-status:closed"));
+        assert!(!matches("-label:frontend"));
+        assert!(matches("-assignee:bob"));
+    }
+
+    #[test]
+    fn epic_filters_should_use_epic_fields() {
+        let mut epic = Epic::new("Login".to_owned(), "".to_owned());
+        epic.owner = Some("bob".to_owned());
+        let filter = Filter::parse("owner:bob id:1", ItemKind::Epic).unwrap();
+
+        assert!(filter.matches_epic(1, &epic));
+        assert!(!filter.matches_epic(2, &epic));
+        assert_eq!(filter.to_string(), "owner:bob id:1");
+    }
+
+    #[test]
+    fn parse_should_report_errors_with_column() {
+        let error = |input: &str| Filter::parse(input, ItemKind::Story).unwrap_err();
+
+        assert_eq!(error(""), "empty filter expression");
+        assert_eq!(error("stat:open"), "unknown field 'stat' at column 1");
+        assert_eq!(
+            error("name~x status=open"),
+            "expected ':' or '~' after 'status' at column 14"
+        );
+        assert_eq!(error("name:"), "missing value for 'name' at column 6");
+        assert_eq!(error(r#"name:"login"#), "unterminated quote at column 6");
+        assert_eq!(error("epic:two"), "invalid id 'two' at column 6");
+        assert_eq!(
+            error("status~open"),
+            "'~' is not supported for 'status', use ':' at column 1"
+        );
+        assert_eq!(
+            error("owner:bob"),
+            "field 'owner' only applies to epics at column 1"
+        );
+        assert!(error("priority:urgent").starts_with("invalid priority 'urgent'"));
+        assert!(Filter::parse("assignee:bob", ItemKind::Epic).is_err());
+    }
+}
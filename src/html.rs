@@ -0,0 +1,171 @@
+use std::io::Write;
+
+use anyhow::Result;
+
+use crate::models::{DBState, Status};
+use crate::ui::pages::page_helpers::get_user_string;
+
+/// Inline stylesheet, so the page has no external assets.
+const STYLE: &str = "\
+body { font-family: sans-serif; margin: 2em auto; max-width: 60em; color: #222; }
+table { border-collapse: collapse; width: 100%; margin: 1em 0; }
+th, td { border-bottom: 1px solid #ddd; padding: 0.4em; text-align: left; vertical-align: top; }
+th { background: #f4f5f7; }
+a { color: #0052cc; text-decoration: none; }
+section { margin-top: 2.5em; }
+.id { color: #6b778c; font-weight: normal; }
+.description { white-space: pre-wrap; }
+.badge { border-radius: 3px; font-size: 0.8em; font-weight: bold; padding: 0.1em 0.5em; }
+.open { background: #dfe1e6; color: #42526e; }
+.in-progress { background: #deebff; color: #0747a6; }
+.resolved { background: #e3fcef; color: #006644; }
+.closed { background: #ebecf0; color: #6b778c; }";
+
+/// Writes a self-contained HTML page with an index of all epics followed by a table of the stories
+/// of each epic. Every epic and story gets an `item-<id>` anchor to link to.
+pub fn export_html(db_state: &DBState, out: &mut dyn Write) -> Result<()> {
+    let mut epic_ids: Vec<&u32> = db_state.epics.keys().collect();
+    epic_ids.sort();
+
+    writeln!(out, "<!DOCTYPE html>")?;
+    writeln!(out, "<html lang=\"en\">")?;
+    writeln!(out, "<head>")?;
+    writeln!(out, "<meta charset=\"utf-8\">")?;
+    writeln!(out, "<title>Epics</title>")?;
+    writeln!(out, "<style>\n{}\n</style>", STYLE)?;
+    writeln!(out, "</head>")?;
+    writeln!(out, "<body>")?;
+    writeln!(out, "<h1>Epics</h1>")?;
+    writeln!(out, "<table>")?;
+    writeln!(
+        out,
+        "<tr><th>Id</th><th>Name</th><th>Status</th><th>Owner</th><th>Stories</th></tr>"
+    )?;
+    for epic_id in &epic_ids {
+        let epic = &db_state.epics[*epic_id];
+        writeln!(
+            out,
+            "<tr><td><a href=\"#item-{id}\">{id}</a></td><td><a href=\"#item-{id}\">{}</a></td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            escape(&epic.name),
+            get_badge(&epic.status),
+            escape(get_user_string(epic.owner.as_deref())),
+            epic.stories.len(),
+            id = epic_id,
+        )?;
+    }
+    writeln!(out, "</table>")?;
+
+    for epic_id in &epic_ids {
+        let epic = &db_state.epics[*epic_id];
+        writeln!(out, "<section id=\"item-{}\">", epic_id)?;
+        writeln!(
+            out,
+            "<h2>{} <span class=\"id\">#{}</span> {}</h2>",
+            escape(&epic.name),
+            epic_id,
+            get_badge(&epic.status)
+        )?;
+        if !epic.description.trim().is_empty() {
+            writeln!(
+                out,
+                "<p class=\"description\">{}</p>",
+                escape(epic.description.trim_end())
+            )?;
+        }
+        writeln!(out, "<table>")?;
+        writeln!(
+            out,
+            "<tr><th>Id</th><th>Name</th><th>Status</th><th>Priority</th><th>Assignee</th></tr>"
+        )?;
+        for story_id in &epic.stories {
+            let Some(story) = db_state.stories.get(story_id) else {
+                continue;
+            };
+            writeln!(
+                out,
+                "<tr id=\"item-{id}\"><td><a href=\"#item-{id}\">{id}</a></td><td>{}</td><td>{}</td><td>{:?}</td><td>{}</td></tr>",
+                escape(&story.name),
+                get_badge(&story.status),
+                story.priority,
+                escape(get_user_string(story.assignee.as_deref())),
+                id = story_id,
+            )?;
+        }
+        writeln!(out, "</table>")?;
+        writeln!(out, "</section>")?;
+    }
+    writeln!(out, "</body>")?;
+    writeln!(out, "</html>")?;
+    Ok(())
+}
+
+fn get_badge(status: &Status) -> String {
+    let class = match status {
+        Status::Open => "open",
+        Status::InProgress => "in-progress",
+        Status::Resolved => "resolved",
+        Status::Closed => "closed",
+    };
+    format!("<span class=\"badge {}\">{:?}</span>", class, status)
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Epic, Story};
+    use std::collections::HashMap;
+
+    fn html() -> String {
+        let mut epic = Epic::new("Login <b>".to_owned(), "Q&A".to_owned());
+        epic.stories = vec![2];
+        let mut story = Story::new("\"Form\"".to_owned(), "".to_owned());
+        story.status = Status::InProgress;
+        let db_state = DBState {
+            last_item_id: 2,
+            epics: HashMap::from([(1, epic)]),
+            stories: HashMap::from([(2, story)]),
+            users: vec![],
+        };
+        let mut out = vec![];
+        export_html(&db_state, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn export_html_should_link_every_item() {
+        let html = html();
+
+        assert!(html.contains("<a href=\"#item-1\">1</a>"));
+        assert!(html.contains("<section id=\"item-1\">"));
+        assert!(html.contains("<tr id=\"item-2\"><td><a href=\"#item-2\">2</a></td>"));
+        assert!(html.contains("<span class=\"badge in-progress\">InProgress</span>"));
+    }
+
+    #[test]
+    fn export_html_should_escape_text_and_inline_styles() {
+        let html = html();
+
+        assert!(html.contains("Login &lt;b&gt;"));
+        assert!(html.contains("<p class=\"description\">Q&amp;A</p>"));
+        assert!(html.contains("<td>&quot;Form&quot;</td>"));
+        assert!(html.contains("<style>"));
+        assert!(!html.contains("<link"));
+        assert!(!html.contains("<script"));
+    }
+
+    #[test]
+    fn escape_should_replace_special_characters() {
+        assert_eq!(
+            escape(r#"<a href="x">'&'</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;&#39;&amp;&#39;&lt;/a&gt;"
+        );
+    }
+}
@@ -1,4 +1,6 @@
-use std::io;
+use std::{env, fs, io, process::Command};
+
+use anyhow::{anyhow, Result};
 
 pub fn get_user_input() -> String {
     let mut user_input = String::new();
@@ -12,3 +14,82 @@ pub fn get_user_input() -> String {
 pub fn wait_for_key_press() {
     io::stdin().read_line(&mut String::new()).unwrap();
 }
+
+/// Width of the terminal in columns, `$COLUMNS` or 80 when stdout is not a terminal.
+pub fn get_terminal_width() -> usize {
+    if let Some((terminal_size::Width(width), _)) = terminal_size::terminal_size() {
+        return width as usize;
+    }
+    env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse().ok())
+        .unwrap_or(80)
+}
+
+const SCISSORS: &str = "# ------------------------ >8 ------------------------";
+
+/// Lets the user edit `text` in `$VISUAL` or `$EDITOR` and returns the result.
+pub fn edit_in_editor(text: &str) -> Result<String> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .map_err(|_| anyhow!("neither $VISUAL nor $EDITOR is set"))?;
+    edit_with(&editor, text)
+}
+
+fn edit_with(editor: &str, text: &str) -> Result<String> {
+    let file = tempfile::Builder::new().suffix(".md").tempfile()?;
+    fs::write(
+        file.path(),
+        format!(
+            "{}\n{}\n# Write the description above, everything below this line is ignored.\n",
+            text, SCISSORS
+        ),
+    )?;
+
+    // the editor may come with arguments, e.g. "code --wait"
+    let mut parts = editor.split_whitespace();
+    let program = parts
+        .next()
+        .ok_or_else(|| anyhow!("editor command is empty"))?;
+    let status = Command::new(program)
+        .args(parts)
+        .arg(file.path())
+        .status()?;
+    if !status.success() {
+        return Err(anyhow!("editor {} exited with {}", editor, status));
+    }
+
+    let edited = fs::read_to_string(file.path())?;
+    let edited = match edited.find(SCISSORS) {
+        Some(index) => &edited[..index],
+        None => &edited,
+    };
+    Ok(edited.trim_end().to_owned())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn edit_with_should_return_text_above_scissors() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("editor.sh");
+        fs::write(
+            &script,
+            "#!/bin/sh\nprintf 'first line\\n- second line\\n' | cat - \"$1\" > \"$1.new\" && mv \"$1.new\" \"$1\"\n",
+        )
+        .unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let edited = edit_with(script.to_str().unwrap(), "old").unwrap();
+        assert_eq!(edited, "first line\n- second line\nold");
+    }
+
+    #[test]
+    fn edit_with_should_fail_if_editor_fails() {
+        assert!(edit_with("false", "text").is_err());
+        assert_eq!(edit_with("true", "text").unwrap(), "text");
+    }
+}
@@ -0,0 +1,469 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    io::Read,
+};
+
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::db::JiraDatabase;
+use crate::models::{validate_label, Epic, Priority, Status, Story};
+
+/// Status names of the default Jira workflows, compared ignoring case. The configured mapping
+/// takes precedence, statuses found in neither fall back to their Jira status category.
+const DEFAULT_STATUS_MAP: &[(&str, Status)] = &[
+    ("backlog", Status::Open),
+    ("to do", Status::Open),
+    ("open", Status::Open),
+    ("selected for development", Status::Open),
+    ("in progress", Status::InProgress),
+    ("in review", Status::InProgress),
+    ("resolved", Status::Resolved),
+    ("done", Status::Closed),
+    ("closed", Status::Closed),
+];
+
+#[derive(Deserialize, Debug)]
+struct Issue {
+    key: String,
+    fields: Fields,
+}
+
+#[derive(Deserialize, Debug)]
+struct Fields {
+    summary: String,
+    /// Plain text, or an Atlassian document in exports of Jira Cloud.
+    #[serde(default)]
+    description: Value,
+    issuetype: Named,
+    status: IssueStatus,
+    #[serde(default)]
+    priority: Option<Named>,
+    #[serde(default)]
+    labels: Vec<String>,
+    #[serde(default)]
+    parent: Option<IssueRef>,
+    /// Epic Link of classic projects, which have no `parent` for stories of an epic.
+    #[serde(default, rename = "customfield_10014")]
+    epic_link: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Named {
+    name: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct IssueStatus {
+    name: String,
+    #[serde(default, rename = "statusCategory")]
+    category: Option<StatusCategory>,
+}
+
+#[derive(Deserialize, Debug)]
+struct StatusCategory {
+    key: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct IssueRef {
+    key: String,
+}
+
+/// Outcome of a Jira import, issues that failed are reported with their key.
+#[derive(Debug, Default, PartialEq)]
+pub struct JiraImportReport {
+    pub epics: usize,
+    pub stories: usize,
+    pub errors: Vec<(String, String)>,
+}
+
+/// Creates an epic for every issue of type Epic in a Jira JSON export and a story for every issue
+/// whose parent (or Epic Link) is one of them, skipping issues with errors. Both record the issue
+/// key, issues that were imported before are reported instead of being imported again.
+///
+/// `status_map` maps Jira status names to statuses, on top of the defaults for the Jira workflows.
+/// The whole import is undone at once.
+pub fn import_jira(
+    db: &JiraDatabase,
+    input: &mut dyn Read,
+    status_map: &HashMap<String, Status>,
+) -> Result<JiraImportReport> {
+    db.batch("import jira".to_owned(), || {
+        import_issues(db, input, status_map)
+    })
+}
+
+fn import_issues(
+    db: &JiraDatabase,
+    input: &mut dyn Read,
+    status_map: &HashMap<String, Status>,
+) -> Result<JiraImportReport> {
+    let export: Value = serde_json::from_reader(input)?;
+    let issues = match &export {
+        Value::Array(issues) => issues,
+        _ => export
+            .get("issues")
+            .and_then(Value::as_array)
+            .ok_or_else(|| anyhow!("expected an array of issues or an object with \"issues\""))?,
+    };
+    let status_map: HashMap<String, Status> = status_map
+        .iter()
+        .map(|(name, status)| (name.to_lowercase(), status.clone()))
+        .collect();
+    let db_state = db.read_db()?;
+    let mut epic_ids: HashMap<String, u32> = db_state
+        .epics
+        .iter()
+        .filter_map(|(id, epic)| Some((epic.jira_key.clone()?, *id)))
+        .collect();
+    let story_ids: HashMap<String, u32> = db_state
+        .stories
+        .iter()
+        .filter_map(|(id, story)| Some((story.jira_key.clone()?, *id)))
+        .collect();
+
+    let mut report = JiraImportReport::default();
+    let mut parsed = vec![];
+    for (index, issue) in issues.iter().enumerate() {
+        match Issue::deserialize(issue) {
+            Ok(issue) => parsed.push(issue),
+            Err(error) => {
+                let key = issue.get("key").and_then(Value::as_str);
+                let key = key.map_or_else(|| format!("issue {}", index + 1), str::to_owned);
+                report.errors.push((key, error.to_string()));
+            }
+        }
+    }
+    let (epics, stories): (Vec<Issue>, Vec<Issue>) = parsed
+        .into_iter()
+        .partition(|issue| issue.fields.issuetype.name.eq_ignore_ascii_case("epic"));
+
+    for issue in epics {
+        let result = match epic_ids.get(&issue.key) {
+            Some(epic_id) => Err(format!("already imported as epic {}", epic_id)),
+            None => to_epic(&issue, &status_map, &db.workflow.statuses),
+        };
+        match result.and_then(|epic| db.create_epic(epic).map_err(|e| format!("{:#}", e))) {
+            Ok(epic_id) => {
+                epic_ids.insert(issue.key, epic_id);
+                report.epics += 1;
+            }
+            Err(error) => report.errors.push((issue.key, error)),
+        }
+    }
+    for issue in stories {
+        let result = match story_ids.get(&issue.key) {
+            Some(story_id) => Err(format!("already imported as story {}", story_id)),
+            None => get_epic_id(&issue, &epic_ids).and_then(|epic_id| {
+                let story = to_story(&issue, &status_map, &db.workflow.statuses)?;
+                db.create_story(story, epic_id)
+                    .map_err(|error| format!("{:#}", error))
+            }),
+        };
+        match result {
+            Ok(_) => report.stories += 1,
+            Err(error) => report.errors.push((issue.key, error)),
+        }
+    }
+    Ok(report)
+}
+
+fn to_epic(
+    issue: &Issue,
+    status_map: &HashMap<String, Status>,
+    statuses: &[Status],
+) -> Result<Epic, String> {
+    let mut epic = Epic::new(
+        issue.fields.summary.clone(),
+        get_description(&issue.fields.description),
+    );
+    epic.status = map_status(&issue.fields.status, status_map, statuses)?;
+    epic.priority = map_priority(issue.fields.priority.as_ref());
+    epic.labels = get_labels(&issue.fields.labels)?;
+    epic.jira_key = Some(issue.key.clone());
+    Ok(epic)
+}
+
+fn to_story(
+    issue: &Issue,
+    status_map: &HashMap<String, Status>,
+    statuses: &[Status],
+) -> Result<Story, String> {
+    let mut story = Story::new(
+        issue.fields.summary.clone(),
+        get_description(&issue.fields.description),
+    );
+    story.status = map_status(&issue.fields.status, status_map, statuses)?;
+    story.priority = map_priority(issue.fields.priority.as_ref());
+    story.labels = get_labels(&issue.fields.labels)?;
+    story.jira_key = Some(issue.key.clone());
+    Ok(story)
+}
+
+/// Id of the epic that an issue belongs to, through its parent or its Epic Link.
+fn get_epic_id(issue: &Issue, epic_ids: &HashMap<String, u32>) -> Result<u32, String> {
+    let parent = issue
+        .fields
+        .parent
+        .as_ref()
+        .map(|parent| &parent.key)
+        .or(issue.fields.epic_link.as_ref())
+        .ok_or_else(|| format!("{} has no parent epic", issue.fields.issuetype.name))?;
+    epic_ids
+        .get(parent)
+        .copied()
+        .ok_or_else(|| format!("parent {} is not an imported epic", parent))
+}
+
+/// Status of the workflow that a Jira status maps to.
+fn map_status(
+    status: &IssueStatus,
+    status_map: &HashMap<String, Status>,
+    statuses: &[Status],
+) -> Result<Status, String> {
+    let name = status.name.to_lowercase();
+    let mapped = status_map
+        .get(&name)
+        .or_else(|| {
+            DEFAULT_STATUS_MAP
+                .iter()
+                .find(|(jira, _)| *jira == name)
+                .map(|(_, status)| status)
+        })
+        .cloned();
+    let mapped = match mapped {
+        Some(mapped) => mapped,
+        None => match status
+            .category
+            .as_ref()
+            .map(|category| category.key.as_str())
+        {
+            Some("new") => Status::Open,
+            Some("indeterminate") => Status::InProgress,
+            Some("done") => Status::Closed,
+            _ => {
+                return Err(format!(
+                    "no mapping for Jira status '{}', add it to \"jira_statuses\" in the config",
+                    status.name
+                ))
+            }
+        },
+    };
+    if !statuses.contains(&mapped) {
+        return Err(format!(
+            "Jira status '{}' maps to {:?}, which is not in the workflow",
+            status.name, mapped
+        ));
+    }
+    Ok(mapped)
+}
+
+/// Priority of the same name, Jira uses the same five levels by default.
+fn map_priority(priority: Option<&Named>) -> Priority {
+    priority
+        .and_then(|priority| priority.name.parse().ok())
+        .unwrap_or_default()
+}
+
+fn get_labels(labels: &[String]) -> Result<BTreeSet<String>, String> {
+    labels
+        .iter()
+        .map(|label| validate_label(label).map(|_| label.clone()))
+        .collect()
+}
+
+/// Text of a plain or Atlassian document description, with a line per paragraph.
+fn get_description(description: &Value) -> String {
+    let mut text = String::new();
+    match description {
+        Value::String(description) => text.push_str(description),
+        Value::Object(_) => push_document_text(description, &mut text),
+        _ => (),
+    }
+    text.trim_end().to_owned()
+}
+
+fn push_document_text(node: &Value, text: &mut String) {
+    match node.get("type").and_then(Value::as_str) {
+        Some("text") => text.push_str(node.get("text").and_then(Value::as_str).unwrap_or("")),
+        Some("hardBreak") => text.push('\n'),
+        _ => (),
+    }
+    for child in node
+        .get("content")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        push_document_text(child, text);
+    }
+    if let Some("paragraph" | "heading" | "codeBlock" | "listItem") =
+        node.get("type").and_then(Value::as_str)
+    {
+        if !text.ends_with('\n') {
+            text.push('\n');
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_utils::MockDB;
+    use crate::workflow::Workflow;
+
+    const EXPORT: &str = r#"{
+        "issues": [
+            {
+                "key": "WEB-2",
+                "fields": {
+                    "summary": "Login form",
+                    "description": "Email and password",
+                    "issuetype": { "name": "Story" },
+                    "status": { "name": "Code Review", "statusCategory": { "key": "indeterminate" } },
+                    "priority": { "name": "High" },
+                    "labels": ["frontend"],
+                    "parent": { "key": "WEB-1" }
+                }
+            },
+            {
+                "key": "WEB-1",
+                "fields": {
+                    "summary": "Login",
+                    "description": {
+                        "type": "doc",
+                        "content": [
+                            { "type": "paragraph", "content": [{ "type": "text", "text": "User login" }] },
+                            { "type": "paragraph", "content": [{ "type": "text", "text": "flow" }] }
+                        ]
+                    },
+                    "issuetype": { "name": "Epic" },
+                    "status": { "name": "In Progress" }
+                }
+            },
+            {
+                "key": "WEB-3",
+                "fields": {
+                    "summary": "Password reset",
+                    "issuetype": { "name": "Task" },
+                    "status": { "name": "Waiting" },
+                    "customfield_10014": "WEB-1"
+                }
+            },
+            {
+                "key": "WEB-4",
+                "fields": {
+                    "summary": "Orphan",
+                    "issuetype": { "name": "Bug" },
+                    "status": { "name": "Done" }
+                }
+            },
+            { "key": "WEB-5", "fields": { "summary": "Broken" } }
+        ]
+    }"#;
+
+    #[test]
+    fn import_should_map_epics_and_their_children() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let status_map = HashMap::from([("waiting".to_owned(), Status::Resolved)]);
+
+        let report = import_jira(&db, &mut EXPORT.as_bytes(), &status_map).unwrap();
+
+        assert_eq!(report.epics, 1);
+        assert_eq!(report.stories, 2);
+        assert_eq!(report.errors.len(), 2);
+        assert!(report.errors[0].0 == "WEB-5" && report.errors[0].1.contains("issuetype"));
+        assert_eq!(
+            report.errors[1],
+            ("WEB-4".to_owned(), "Bug has no parent epic".to_owned())
+        );
+
+        let db_state = db.read_db().unwrap();
+        let (_, epic) = db_state.epics.iter().next().unwrap();
+        assert_eq!(epic.jira_key.as_deref(), Some("WEB-1"));
+        assert_eq!(epic.description, "User login\nflow");
+        assert_eq!(epic.status, Status::InProgress);
+        assert_eq!(epic.stories.len(), 2);
+
+        let form = &db_state.stories[&epic.stories[0]];
+        assert_eq!(form.jira_key.as_deref(), Some("WEB-2"));
+        assert_eq!(form.status, Status::InProgress);
+        assert_eq!(form.priority, Priority::High);
+        assert!(form.labels.contains("frontend"));
+
+        let reset = &db_state.stories[&epic.stories[1]];
+        assert_eq!(reset.status, Status::Resolved);
+        assert_eq!(reset.priority, Priority::Medium);
+    }
+
+    #[test]
+    fn import_should_not_import_issues_twice() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let status_map = HashMap::from([("waiting".to_owned(), Status::Resolved)]);
+        import_jira(&db, &mut EXPORT.as_bytes(), &status_map).unwrap();
+
+        let report = import_jira(&db, &mut EXPORT.as_bytes(), &status_map).unwrap();
+
+        assert_eq!(report.epics + report.stories, 0);
+        assert!(report
+            .errors
+            .iter()
+            .any(|(key, error)| key == "WEB-1" && error.starts_with("already imported as epic")));
+        assert!(report
+            .errors
+            .iter()
+            .any(|(key, error)| key == "WEB-2" && error.starts_with("already imported as story")));
+        assert_eq!(db.read_db().unwrap().stories.len(), 2);
+
+        assert_eq!(db.undo().unwrap(), Some("import jira".to_owned()));
+        assert!(db.list_epics().unwrap().is_empty());
+    }
+
+    #[test]
+    fn unmapped_statuses_should_be_reported() {
+        let db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        let input = r#"[{ "key": "OPS-1", "fields": {
+            "summary": "Ops", "issuetype": { "name": "Epic" }, "status": { "name": "Waiting" }
+        } }]"#;
+
+        let report = import_jira(&db, &mut input.as_bytes(), &HashMap::new()).unwrap();
+
+        assert_eq!(report.epics, 0);
+        assert_eq!(
+            report.errors,
+            vec![(
+                "OPS-1".to_owned(),
+                "no mapping for Jira status 'Waiting', add it to \"jira_statuses\" in the config"
+                    .to_owned()
+            )]
+        );
+        assert!(import_jira(&db, &mut "{}".as_bytes(), &HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn statuses_outside_the_workflow_should_be_reported() {
+        let mut db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        db.workflow = Workflow {
+            statuses: vec![Status::Open, Status::InProgress, Status::Closed],
+            ..Workflow::default()
+        };
+        let status_map = HashMap::from([("waiting".to_owned(), Status::Resolved)]);
+
+        let report = import_jira(&db, &mut EXPORT.as_bytes(), &status_map).unwrap();
+
+        assert_eq!(report.epics, 1);
+        assert_eq!(report.stories, 1);
+        assert!(report.errors.contains(&(
+            "WEB-3".to_owned(),
+            "Jira status 'Waiting' maps to Resolved, which is not in the workflow".to_owned()
+        )));
+        assert!(db
+            .read_db()
+            .unwrap()
+            .stories
+            .values()
+            .all(|story| story.status != Status::Resolved));
+    }
+}
@@ -1,15 +1,49 @@
-use std::rc::Rc;
+use std::{io, process::ExitCode, rc::Rc};
 
+use clap::Parser;
+
+mod cli;
+mod config;
+mod csv_io;
 mod db;
+mod filter;
+mod html;
 mod io_utils;
+mod jira;
 mod models;
 mod navigator;
+mod output;
+mod report;
+mod search;
 mod ui;
+mod workflow;
 use io_utils::*;
 use navigator::Navigator;
-fn main() {
-    let db = Rc::new(db::JiraDatabase::new("data/db.json".to_string()));
+fn main() -> ExitCode {
+    let cli = cli::Cli::parse();
+    let config = match config::Config::load(&cli.config) {
+        Ok(config) => config,
+        Err(error) => return exit_with_error(error),
+    };
+    let db = match cli.open_database() {
+        Ok(mut db) => {
+            // changes are attributed to the current user in the change log
+            db.actor = config.current_user();
+            db.workflow = config.workflow.clone();
+            Rc::new(db)
+        }
+        Err(error) => return exit_with_error(error),
+    };
+
+    if cli.command.is_some() {
+        return match cli::run(cli, &db, &mut io::stdout()) {
+            Ok(_) => ExitCode::SUCCESS,
+            Err(error) => exit_with_error(error),
+        };
+    }
+
     let mut navigator = Navigator::new(db);
+    navigator.current_user = config.current_user();
     loop {
         clearscreen::clear().unwrap();
         let current_page = navigator.get_current_page();
@@ -25,9 +59,9 @@ fn main() {
             let input = get_user_input();
 
             if let Ok(Some(action)) = page.handle_input(input.as_str()) {
-                match navigator.handle_action(action) {
-                    Ok(_) => (),
-                    Err(_) => break,
+                if let Err(error) = navigator.handle_action(action) {
+                    println!("Error: {:#}\nPress any key to continue...", error);
+                    wait_for_key_press();
                 }
             } else {
                 println!("Error getting user input: \nPress any key to continue...");
@@ -37,4 +71,9 @@ fn main() {
             break;
         }
     }
+    ExitCode::SUCCESS
+}
+fn exit_with_error(error: anyhow::Error) -> ExitCode {
+    eprintln!("Error: {:#}", error);
+    ExitCode::FAILURE
 }
@@ -1,27 +1,100 @@
+use crate::filter::Filter;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashMap,
+    collections::{BTreeSet, HashMap},
     fmt::{Display, Formatter, Result},
+    str::FromStr,
 };
 //use anyhow::{anyhow, Result};
 
-#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Status {
     Open,
     InProgress,
     Resolved,
     Closed,
 }
+impl Status {
+    /// Statuses in the order of the board columns.
+    pub const ALL: [Status; 4] = [
+        Status::Open,
+        Status::InProgress,
+        Status::Resolved,
+        Status::Closed,
+    ];
+}
 impl Display for Status {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         write!(f, "\"{:?}\"", self)
     }
 }
+impl FromStr for Status {
+    type Err = String;
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().replace(['-', '_'], "").as_str() {
+            "open" => Ok(Status::Open),
+            "inprogress" => Ok(Status::InProgress),
+            "resolved" => Ok(Status::Resolved),
+            "closed" => Ok(Status::Closed),
+            _ => Err(format!(
+                "invalid status '{}' (expected open, in-progress, resolved or closed)",
+                s
+            )),
+        }
+    }
+}
+/// Triage priority, ordered from `Highest` to `Lowest`.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    Highest,
+    High,
+    #[default]
+    Medium,
+    Low,
+    Lowest,
+}
+impl Display for Priority {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "\"{:?}\"", self)
+    }
+}
+impl FromStr for Priority {
+    type Err = String;
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "highest" => Ok(Priority::Highest),
+            "high" => Ok(Priority::High),
+            "medium" => Ok(Priority::Medium),
+            "low" => Ok(Priority::Low),
+            "lowest" => Ok(Priority::Lowest),
+            _ => Err(format!(
+                "invalid priority '{}' (expected highest, high, medium, low or lowest)",
+                s
+            )),
+        }
+    }
+}
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct Epic {
     pub name: String,
     pub description: String,
     pub status: Status,
+    #[serde(default)]
+    pub priority: Priority,
+    /// Username of the owner, `None` while unowned.
+    #[serde(default)]
+    pub owner: Option<String>,
+    #[serde(default)]
+    pub labels: BTreeSet<String>,
+    /// Set when the epic is created through `JiraDatabase`, `None` for older items.
+    #[serde(default)]
+    pub created: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub updated: Option<DateTime<Utc>>,
+    /// Key of the Jira issue the epic was imported from, e.g. `PROJ-1`.
+    #[serde(default)]
+    pub jira_key: Option<String>,
     pub stories: Vec<u32>,
 }
 impl Epic {
@@ -30,6 +103,12 @@ impl Epic {
             name,
             description,
             status: Status::Open,
+            priority: Priority::default(),
+            owner: None,
+            labels: BTreeSet::new(),
+            created: None,
+            updated: None,
+            jira_key: None,
             stories: vec![],
         }
     }
@@ -39,6 +118,24 @@ pub struct Story {
     pub name: String,
     pub description: String,
     pub status: Status,
+    #[serde(default)]
+    pub priority: Priority,
+    /// Username of the assignee, `None` while unassigned.
+    #[serde(default)]
+    pub assignee: Option<String>,
+    #[serde(default)]
+    pub labels: BTreeSet<String>,
+    /// Set when the story is created through `JiraDatabase`, `None` for older items.
+    #[serde(default)]
+    pub created: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub updated: Option<DateTime<Utc>>,
+    /// Key of the Jira issue the story was imported from, e.g. `PROJ-2`.
+    #[serde(default)]
+    pub jira_key: Option<String>,
+    /// Discussion of the story, oldest comment first.
+    #[serde(default)]
+    pub comments: Vec<Comment>,
 }
 impl Story {
     pub fn new(name: String, description: String) -> Self {
@@ -46,26 +143,237 @@ impl Story {
             name,
             description,
             status: Status::Open,
+            priority: Priority::default(),
+            assignee: None,
+            labels: BTreeSet::new(),
+            created: None,
+            updated: None,
+            jira_key: None,
+            comments: vec![],
         }
     }
 }
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Comment {
+    /// Username of whoever wrote the comment, `None` if unknown.
+    pub author: Option<String>,
+    pub time: DateTime<Utc>,
+    pub body: String,
+}
+/// Kind of item, for code that handles epics and stories alike.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemKind {
+    Epic,
+    Story,
+}
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct DBState {
     pub last_item_id: u32,
     pub epics: HashMap<u32, Epic>,
     pub stories: HashMap<u32, Story>,
+    #[serde(default)]
+    pub users: Vec<User>,
+}
+/// Checks that a label is a single non-empty word, so it can be listed and filtered unambiguously.
+pub fn validate_label(label: &str) -> std::result::Result<(), String> {
+    if label.is_empty() || label.contains(|c: char| c.is_whitespace() || c == ',') {
+        return Err(format!(
+            "invalid label '{}' (expected a non-empty word without spaces or commas)",
+            label
+        ));
+    }
+    Ok(())
+}
+/// Registered user that epics and stories can be assigned to.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct User {
+    pub username: String,
+    pub name: String,
+}
+impl User {
+    pub fn new(username: String, name: String) -> Self {
+        Self { username, name }
+    }
+}
+
+/// Snapshot of one item before and after a change, `None` when it did not exist.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ItemChange<T> {
+    pub id: u32,
+    pub before: Option<T>,
+    pub after: Option<T>,
+}
+/// All items touched by one mutating operation, enough to undo or redo it.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Change {
+    pub description: String,
+    pub epics: Vec<ItemChange<Epic>>,
+    pub stories: Vec<ItemChange<Story>>,
+}
+impl Change {
+    pub fn new(description: String) -> Self {
+        Self {
+            description,
+            epics: vec![],
+            stories: vec![],
+        }
+    }
+    pub fn epic(mut self, id: u32, before: Option<Epic>, after: Option<Epic>) -> Self {
+        self.epics.push(ItemChange { id, before, after });
+        self
+    }
+    pub fn story(mut self, id: u32, before: Option<Story>, after: Option<Story>) -> Self {
+        self.stories.push(ItemChange { id, before, after });
+        self
+    }
+    pub fn is_empty(&self) -> bool {
+        self.epics.is_empty() && self.stories.is_empty()
+    }
+    /// Adds a later change to this one, items changed by both keep their first `before`.
+    pub fn merge(&mut self, later: Change) {
+        merge_items(&mut self.epics, later.epics);
+        merge_items(&mut self.stories, later.stories);
+    }
+}
+fn merge_items<T>(items: &mut Vec<ItemChange<T>>, later: Vec<ItemChange<T>>) {
+    for item in later {
+        match items.iter_mut().find(|existing| existing.id == item.id) {
+            Some(existing) => existing.after = item.after,
+            None => items.push(item),
+        }
+    }
+}
+/// One field of an item changed by a mutation, the change log only ever grows.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    pub item_id: u32,
+    /// Name of the changed field, `created` or `deleted` for the item as a whole.
+    pub field: String,
+    /// JSON of the value before the change, `None` if there was none.
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub time: DateTime<Utc>,
+    /// Username of whoever made the change, `None` if unknown.
+    pub actor: Option<String>,
+}
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct History {
+    pub undo: Vec<Change>,
+    pub redo: Vec<Change>,
 }
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum Action {
-    NavigateToEpicDetail { epic_id: u32 },
-    NavigateToStoryDetail { epic_id: u32, story_id: u32 },
+    NavigateToEpicDetail {
+        epic_id: u32,
+    },
+    NavigateToStoryDetail {
+        epic_id: u32,
+        story_id: u32,
+    },
     NavigateToPreviousPage,
+    NavigateToMyWork,
+    /// Board of all stories, or of the stories of one epic.
+    NavigateToBoard {
+        epic_id: Option<u32>,
+    },
+    SelectCard {
+        epic_id: Option<u32>,
+        story_id: u32,
+    },
+    MoveCard {
+        story_id: u32,
+        status: Status,
+    },
+    Search,
+    FilterEpicsByLabel {
+        query: Option<Filter>,
+    },
+    FilterEpicsByQuery {
+        label_filter: Option<String>,
+    },
+    FilterStoriesByLabel {
+        epic_id: u32,
+        sort_by_priority: bool,
+        query: Option<Filter>,
+    },
+    FilterStoriesByQuery {
+        epic_id: u32,
+        sort_by_priority: bool,
+        label_filter: Option<String>,
+    },
+    SortStories {
+        epic_id: u32,
+        by_priority: bool,
+        label_filter: Option<String>,
+        query: Option<Filter>,
+    },
     CreateEpic,
-    UpdateEpicStatus { epic_id: u32 },
-    DeleteEpic { epic_id: u32 },
-    CreateStory { epic_id: u32 },
-    UpdateStoryStatus { story_id: u32 },
-    DeleteStory { epic_id: u32, story_id: u32 },
+    UpdateEpic {
+        epic_id: u32,
+    },
+    UpdateEpicStatus {
+        epic_id: u32,
+    },
+    UpdateEpicPriority {
+        epic_id: u32,
+    },
+    AssignEpic {
+        epic_id: u32,
+    },
+    UnassignEpic {
+        epic_id: u32,
+    },
+    AddEpicLabel {
+        epic_id: u32,
+    },
+    RemoveEpicLabel {
+        epic_id: u32,
+    },
+    DeleteEpic {
+        epic_id: u32,
+    },
+    CreateStory {
+        epic_id: u32,
+    },
+    UpdateStory {
+        story_id: u32,
+    },
+    UpdateStoryStatus {
+        story_id: u32,
+    },
+    UpdateStoryPriority {
+        story_id: u32,
+    },
+    AssignStory {
+        story_id: u32,
+    },
+    UnassignStory {
+        story_id: u32,
+    },
+    AddStoryLabel {
+        story_id: u32,
+    },
+    RemoveStoryLabel {
+        story_id: u32,
+    },
+    AddComment {
+        story_id: u32,
+    },
+    PageComments {
+        epic_id: u32,
+        story_id: u32,
+        page: usize,
+    },
+    MoveStory {
+        epic_id: u32,
+        story_id: u32,
+    },
+    DeleteStory {
+        epic_id: u32,
+        story_id: u32,
+    },
+    Undo,
+    Redo,
     Exit,
 }
@@ -1,25 +1,33 @@
 use anyhow::{anyhow, Context, Ok, Result};
+use itertools::Itertools;
 use std::rc::Rc;
 
 use crate::db::JiraDatabase;
-use crate::models::Action;
-use crate::ui::pages::{EpicDetail, HomePage, Page, StoryDetail};
+use crate::models::{Action, ItemKind, Status};
+use crate::ui::pages::{BoardPage, EpicDetail, HomePage, MyWork, Page, SearchPage, StoryDetail};
 use crate::ui::prompts::Prompts;
 pub struct Navigator {
     pub pages: Vec<Box<dyn Page>>,
     pub prompts: Prompts,
     pub db: Rc<JiraDatabase>,
+    /// Username whose work the "my work" page shows.
+    pub current_user: Option<String>,
 }
 impl Navigator {
     pub fn new(db: Rc<JiraDatabase>) -> Self {
         Self {
-            pages: vec![Box::new(HomePage { db: Rc::clone(&db) })],
+            pages: vec![Box::new(HomePage {
+                db: Rc::clone(&db),
+                label_filter: None,
+                query: None,
+            })],
             prompts: Prompts::new(),
             db,
+            current_user: None,
         }
     }
-    pub fn get_current_page(&self) -> Option<&Box<dyn Page>> {
-        self.pages.last()
+    pub fn get_current_page(&self) -> Option<&dyn Page> {
+        self.pages.last().map(|page| page.as_ref())
     }
     pub fn handle_action(&mut self, action: Action) -> Result<()> {
         match action {
@@ -28,6 +36,9 @@ impl Navigator {
                 let epic_detail = EpicDetail {
                     epic_id,
                     db: Rc::clone(&self.db),
+                    sort_by_priority: false,
+                    label_filter: None,
+                    query: None,
                 };
                 self.pages.push(Box::new(epic_detail));
             }
@@ -37,9 +48,117 @@ impl Navigator {
                     epic_id,
                     story_id,
                     db: Rc::clone(&self.db),
+                    comment_page: 0,
                 };
                 self.pages.push(Box::new(story_detail));
             }
+            Action::NavigateToMyWork => {
+                // create a new MyWork instance for the current user and add it to the pages vector
+                self.pages.push(Box::new(MyWork {
+                    user: self.current_user.clone(),
+                    db: Rc::clone(&self.db),
+                }));
+            }
+            Action::NavigateToBoard { epic_id } => {
+                // create a new BoardPage instance without a selected card and add it to the pages vector
+                self.pages.push(Box::new(BoardPage {
+                    epic_id,
+                    db: Rc::clone(&self.db),
+                    selected: None,
+                }));
+            }
+            Action::SelectCard { epic_id, story_id } => {
+                // replace the current board page with one where the card is selected
+                self.pages.pop();
+                self.pages.push(Box::new(BoardPage {
+                    epic_id,
+                    db: Rc::clone(&self.db),
+                    selected: Some(story_id),
+                }));
+            }
+            Action::MoveCard { story_id, status } => {
+                // persist the status of the column the card was moved to
+                self.db
+                    .update_story_status(story_id, status)
+                    .with_context(|| anyhow!("failed to move card"))?;
+            }
+            Action::Search => {
+                // prompt the user for a query and add a page with its results
+                if let Some(query) = (self.prompts.search)() {
+                    self.pages.push(Box::new(SearchPage {
+                        query,
+                        db: Rc::clone(&self.db),
+                    }));
+                }
+            }
+            Action::SortStories {
+                epic_id,
+                by_priority,
+                label_filter,
+                query,
+            } => {
+                // replace the current epic page with one in the requested story order
+                self.pages.pop();
+                self.pages.push(Box::new(EpicDetail {
+                    epic_id,
+                    db: Rc::clone(&self.db),
+                    sort_by_priority: by_priority,
+                    label_filter,
+                    query,
+                }));
+            }
+            Action::FilterEpicsByLabel { query } => {
+                // prompt the user for a label and replace the home page with a filtered one
+                let label_filter = (self.prompts.filter_label)();
+                self.pages.pop();
+                self.pages.push(Box::new(HomePage {
+                    db: Rc::clone(&self.db),
+                    label_filter,
+                    query,
+                }));
+            }
+            Action::FilterEpicsByQuery { label_filter } => {
+                // prompt the user for a filter expression and replace the home page with a filtered one
+                let query = (self.prompts.filter_query)(ItemKind::Epic);
+                self.pages.pop();
+                self.pages.push(Box::new(HomePage {
+                    db: Rc::clone(&self.db),
+                    label_filter,
+                    query,
+                }));
+            }
+            Action::FilterStoriesByLabel {
+                epic_id,
+                sort_by_priority,
+                query,
+            } => {
+                // prompt the user for a label and replace the epic page with a filtered one
+                let label_filter = (self.prompts.filter_label)();
+                self.pages.pop();
+                self.pages.push(Box::new(EpicDetail {
+                    epic_id,
+                    db: Rc::clone(&self.db),
+                    sort_by_priority,
+                    label_filter,
+                    query,
+                }));
+            }
+            Action::FilterStoriesByQuery {
+                epic_id,
+                sort_by_priority,
+                label_filter,
+            } => {
+                // prompt the user for a filter expression and replace the epic page with a filtered one
+                let query = (self.prompts.filter_query)(ItemKind::Story);
+                self.pages.pop();
+                self.pages.push(Box::new(EpicDetail {
+                    epic_id,
+                    db: Rc::clone(&self.db),
+                    sort_by_priority,
+                    label_filter,
+                    query,
+                }));
+            }
             Action::NavigateToPreviousPage => {
                 // remove the last page from the pages vector
                 if !self.pages.is_empty() {
@@ -53,13 +172,76 @@ impl Navigator {
                     .create_epic(epic)
                     .with_context(|| anyhow!("failed to create epic"))?;
             }
+            Action::UpdateEpic { epic_id } => {
+                // prompt the user to edit name and description and persist them in the database
+                let epic = self
+                    .db
+                    .get_epic(epic_id)?
+                    .ok_or_else(|| anyhow!("could not find epic with id {}", epic_id))?;
+                let epic = (self.prompts.update_epic)(&epic);
+                self.db
+                    .update_epic_details(epic_id, epic.name, epic.description)
+                    .with_context(|| anyhow!("failed to update epic"))?;
+            }
             Action::UpdateEpicStatus { epic_id } => {
-                // prompt the user to update status and persist it in the database
-                let status = (self.prompts.update_status)();
+                // prompt the user for one of the allowed next statuses and persist it in the database
+                let epic = self
+                    .db
+                    .get_epic(epic_id)?
+                    .ok_or_else(|| anyhow!("could not find epic with id {}", epic_id))?;
+                let status =
+                    (self.prompts.update_status)(&self.db.workflow.next_statuses(&epic.status));
                 if let Some(status) = status {
+                    // closing an epic with stories still open needs to be confirmed
+                    let open_stories = match status {
+                        Status::Closed => self.db.open_stories(epic_id)?,
+                        _ => vec![],
+                    };
+                    if open_stories.is_empty() || (self.prompts.close_epic)(&open_stories) {
+                        self.db
+                            .update_epic_status(epic_id, status)
+                            .with_context(|| anyhow!("failed to update epic status"))?;
+                    }
+                }
+            }
+            Action::UpdateEpicPriority { epic_id } => {
+                // prompt the user to update priority and persist it in the database
+                let priority = (self.prompts.update_priority)();
+                if let Some(priority) = priority {
+                    self.db
+                        .update_epic_priority(epic_id, priority)
+                        .with_context(|| anyhow!("failed to update epic priority"))?;
+                }
+            }
+            Action::AssignEpic { epic_id } => {
+                // prompt the user for the new owner and persist it in the database
+                let users = self.db.list_users()?;
+                if let Some(username) = (self.prompts.assign)(&users) {
+                    self.db
+                        .assign_epic(epic_id, Some(username))
+                        .with_context(|| anyhow!("failed to assign epic"))?;
+                }
+            }
+            Action::UnassignEpic { epic_id } => {
+                // remove the owner of the epic in the database
+                self.db
+                    .assign_epic(epic_id, None)
+                    .with_context(|| anyhow!("failed to unassign epic"))?;
+            }
+            Action::AddEpicLabel { epic_id } => {
+                // prompt the user for a label and add it to the epic in the database
+                if let Some(label) = (self.prompts.label)() {
+                    self.db
+                        .label_epic(epic_id, &label, true)
+                        .with_context(|| anyhow!("failed to add epic label"))?;
+                }
+            }
+            Action::RemoveEpicLabel { epic_id } => {
+                // prompt the user for a label and remove it from the epic in the database
+                if let Some(label) = (self.prompts.label)() {
                     self.db
-                        .update_epic_status(epic_id, status)
-                        .with_context(|| anyhow!("failed to update epic status"))?;
+                        .label_epic(epic_id, &label, false)
+                        .with_context(|| anyhow!("failed to remove epic label"))?;
                 }
             }
             Action::DeleteEpic { epic_id } => {
@@ -81,15 +263,117 @@ impl Navigator {
                     .create_story(story, epic_id)
                     .with_context(|| anyhow!("failed to create story"))?;
             }
+            Action::UpdateStory { story_id } => {
+                // prompt the user to edit name and description and persist them in the database
+                let story = self
+                    .db
+                    .get_story(story_id)?
+                    .ok_or_else(|| anyhow!("could not find story with id {}", story_id))?;
+                let story = (self.prompts.update_story)(&story);
+                self.db
+                    .update_story_details(story_id, story.name, story.description)
+                    .with_context(|| anyhow!("failed to update story"))?;
+            }
             Action::UpdateStoryStatus { story_id } => {
-                // prompt the user to update status and persist it in the database
-                let status = (self.prompts.update_status)();
+                // prompt the user for one of the allowed next statuses and persist it in the database
+                let story = self
+                    .db
+                    .get_story(story_id)?
+                    .ok_or_else(|| anyhow!("could not find story with id {}", story_id))?;
+                let status =
+                    (self.prompts.update_status)(&self.db.workflow.next_statuses(&story.status));
                 if let Some(status) = status {
                     self.db
                         .update_story_status(story_id, status)
                         .with_context(|| anyhow!("failed to update story status"))?;
                 }
             }
+            Action::UpdateStoryPriority { story_id } => {
+                // prompt the user to update priority and persist it in the database
+                let priority = (self.prompts.update_priority)();
+                if let Some(priority) = priority {
+                    self.db
+                        .update_story_priority(story_id, priority)
+                        .with_context(|| anyhow!("failed to update story priority"))?;
+                }
+            }
+            Action::AssignStory { story_id } => {
+                // prompt the user for the new assignee and persist it in the database
+                let users = self.db.list_users()?;
+                if let Some(username) = (self.prompts.assign)(&users) {
+                    self.db
+                        .assign_story(story_id, Some(username))
+                        .with_context(|| anyhow!("failed to assign story"))?;
+                }
+            }
+            Action::UnassignStory { story_id } => {
+                // remove the assignee of the story in the database
+                self.db
+                    .assign_story(story_id, None)
+                    .with_context(|| anyhow!("failed to unassign story"))?;
+            }
+            Action::AddStoryLabel { story_id } => {
+                // prompt the user for a label and add it to the story in the database
+                if let Some(label) = (self.prompts.label)() {
+                    self.db
+                        .label_story(story_id, &label, true)
+                        .with_context(|| anyhow!("failed to add story label"))?;
+                }
+            }
+            Action::RemoveStoryLabel { story_id } => {
+                // prompt the user for a label and remove it from the story in the database
+                if let Some(label) = (self.prompts.label)() {
+                    self.db
+                        .label_story(story_id, &label, false)
+                        .with_context(|| anyhow!("failed to remove story label"))?;
+                }
+            }
+            Action::AddComment { story_id } => {
+                // prompt the user for a comment and append it to the story in the database
+                if let Some(body) = (self.prompts.add_comment)() {
+                    self.db
+                        .add_comment(story_id, body)
+                        .with_context(|| anyhow!("failed to add comment"))?;
+                }
+            }
+            Action::PageComments {
+                epic_id,
+                story_id,
+                page,
+            } => {
+                // replace the current story page with one showing the requested comments
+                self.pages.pop();
+                self.pages.push(Box::new(StoryDetail {
+                    epic_id,
+                    story_id,
+                    db: Rc::clone(&self.db),
+                    comment_page: page,
+                }));
+            }
+            Action::MoveStory { epic_id, story_id } => {
+                // prompt the user for the target epic and move the story there
+                let epics: Vec<(u32, String)> = self
+                    .db
+                    .list_epics()?
+                    .into_iter()
+                    .filter(|(id, _)| *id != epic_id)
+                    .sorted_by_key(|(id, _)| *id)
+                    .map(|(id, epic)| (id, epic.name))
+                    .collect();
+                if let Some(to_epic_id) = (self.prompts.move_story)(&epics) {
+                    self.db
+                        .move_story(story_id, epic_id, to_epic_id)
+                        .with_context(|| anyhow!("failed to move story"))?;
+                    // the story page now belongs to the new epic
+                    self.pages.pop();
+                    self.pages.push(Box::new(StoryDetail {
+                        epic_id: to_epic_id,
+                        story_id,
+                        db: Rc::clone(&self.db),
+                        comment_page: 0,
+                    }));
+                }
+            }
             Action::DeleteStory { epic_id, story_id } => {
                 // prompt the user to delete the story and persist it in the database
                 let delete_story = (self.prompts.delete_story)();
@@ -102,6 +386,14 @@ impl Navigator {
                     self.pages.pop();
                 }
             }
+            Action::Undo => {
+                // revert the latest change recorded in the database history
+                self.db.undo().with_context(|| anyhow!("failed to undo"))?;
+            }
+            Action::Redo => {
+                // apply the latest undone change again
+                self.db.redo().with_context(|| anyhow!("failed to redo"))?;
+            }
             Action::Exit => {
                 // remove all pages from the pages vector
                 self.pages.clear()
@@ -126,14 +418,15 @@ mod tests {
     use super::*;
     use crate::{
         db::test_utils::MockDB,
-        models::{Epic, Status, Story},
+        filter::Filter,
+        models::{Epic, Priority, Status, Story, User},
+        workflow::Workflow,
     };
+    use std::collections::HashMap;
 
     #[test]
     fn should_start_on_home_page() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
-        });
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
         let nav = Navigator::new(db);
 
         assert_eq!(nav.get_page_count(), 1);
@@ -141,14 +434,12 @@ mod tests {
         let current_page = nav.get_current_page().unwrap();
         let home_page = current_page.as_any().downcast_ref::<HomePage>();
 
-        assert_eq!(home_page.is_some(), true);
+        assert!(home_page.is_some());
     }
 
     #[test]
     fn handle_action_should_navigate_pages() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
-        });
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
 
         let mut nav = Navigator::new(db);
 
@@ -158,7 +449,7 @@ mod tests {
 
         let current_page = nav.get_current_page().unwrap();
         let epic_detail_page = current_page.as_any().downcast_ref::<EpicDetail>();
-        assert_eq!(epic_detail_page.is_some(), true);
+        assert!(epic_detail_page.is_some());
 
         nav.handle_action(Action::NavigateToStoryDetail {
             epic_id: 1,
@@ -169,21 +460,21 @@ mod tests {
 
         let current_page = nav.get_current_page().unwrap();
         let story_detail_page = current_page.as_any().downcast_ref::<StoryDetail>();
-        assert_eq!(story_detail_page.is_some(), true);
+        assert!(story_detail_page.is_some());
 
         nav.handle_action(Action::NavigateToPreviousPage).unwrap();
         assert_eq!(nav.get_page_count(), 2);
 
         let current_page = nav.get_current_page().unwrap();
         let epic_detail_page = current_page.as_any().downcast_ref::<EpicDetail>();
-        assert_eq!(epic_detail_page.is_some(), true);
+        assert!(epic_detail_page.is_some());
 
         nav.handle_action(Action::NavigateToPreviousPage).unwrap();
         assert_eq!(nav.get_page_count(), 1);
 
         let current_page = nav.get_current_page().unwrap();
         let home_page = current_page.as_any().downcast_ref::<HomePage>();
-        assert_eq!(home_page.is_some(), true);
+        assert!(home_page.is_some());
 
         nav.handle_action(Action::NavigateToPreviousPage).unwrap();
         assert_eq!(nav.get_page_count(), 0);
@@ -194,9 +485,7 @@ mod tests {
 
     #[test]
     fn handle_action_should_clear_pages_on_exit() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
-        });
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
 
         let mut nav = Navigator::new(db);
 
@@ -214,9 +503,7 @@ mod tests {
 
     #[test]
     fn handle_action_should_handle_create_epic() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
-        });
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
 
         let mut nav = Navigator::new(Rc::clone(&db));
 
@@ -237,9 +524,7 @@ mod tests {
 
     #[test]
     fn handle_action_should_handle_update_epic() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
-        });
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
         let epic_id = db
             .create_epic(Epic::new("".to_owned(), "".to_owned()))
             .unwrap();
@@ -247,7 +532,7 @@ mod tests {
         let mut nav = Navigator::new(Rc::clone(&db));
 
         let mut prompts = Prompts::new();
-        prompts.update_status = Box::new(|| Some(Status::InProgress));
+        prompts.update_status = Box::new(|_| Some(Status::InProgress));
 
         nav.set_prompts(prompts);
 
@@ -262,10 +547,33 @@ mod tests {
     }
 
     #[test]
-    fn handle_action_should_handle_delete_epic() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
+    fn handle_action_should_handle_edit_epic() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("name".to_owned(), "description".to_owned()))
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let mut prompts = Prompts::new();
+        prompts.update_epic = Box::new(|epic| {
+            let mut epic = epic.clone();
+            epic.name = format!("new {}", epic.name);
+            epic
         });
+
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::UpdateEpic { epic_id }).unwrap();
+
+        let epic = db.get_epic(epic_id).unwrap().unwrap();
+        assert_eq!(epic.name, "new name".to_owned());
+        assert_eq!(epic.description, "description".to_owned());
+    }
+
+    #[test]
+    fn handle_action_should_handle_delete_epic() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
         let epic_id = db
             .create_epic(Epic::new("".to_owned(), "".to_owned()))
             .unwrap();
@@ -285,9 +593,7 @@ mod tests {
 
     #[test]
     fn handle_action_should_handle_create_story() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
-        });
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
         let epic_id = db
             .create_epic(Epic::new("".to_owned(), "".to_owned()))
             .unwrap();
@@ -311,9 +617,7 @@ mod tests {
 
     #[test]
     fn handle_action_should_handle_update_story() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
-        });
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
         let epic_id = db
             .create_epic(Epic::new("".to_owned(), "".to_owned()))
             .unwrap();
@@ -324,7 +628,7 @@ mod tests {
         let mut nav = Navigator::new(Rc::clone(&db));
 
         let mut prompts = Prompts::new();
-        prompts.update_status = Box::new(|| Some(Status::InProgress));
+        prompts.update_status = Box::new(|_| Some(Status::InProgress));
 
         nav.set_prompts(prompts);
 
@@ -339,10 +643,321 @@ mod tests {
     }
 
     #[test]
-    fn handle_action_should_handle_delete_story() {
-        let db = Rc::new(JiraDatabase {
-            database: Box::new(MockDB::new()),
+    fn handle_action_should_handle_edit_story() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(
+                Story::new("name".to_owned(), "description".to_owned()),
+                epic_id,
+            )
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let mut prompts = Prompts::new();
+        prompts.update_story = Box::new(|story| {
+            let mut story = story.clone();
+            story.description = "new description".to_owned();
+            story
         });
+
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::UpdateStory { story_id }).unwrap();
+
+        let story = db.get_story(story_id).unwrap().unwrap();
+        assert_eq!(story.name, "name".to_owned());
+        assert_eq!(story.description, "new description".to_owned());
+    }
+
+    #[test]
+    fn handle_action_should_handle_move_story() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let epic_1 = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let epic_2 = db
+            .create_epic(Epic::new("target".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_1)
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let mut prompts = Prompts::new();
+        prompts.move_story = Box::new(|epics| {
+            assert_eq!(epics.len(), 1);
+            Some(epics[0].0)
+        });
+
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::NavigateToStoryDetail {
+            epic_id: epic_1,
+            story_id,
+        })
+        .unwrap();
+        nav.handle_action(Action::MoveStory {
+            epic_id: epic_1,
+            story_id,
+        })
+        .unwrap();
+
+        let db_state = db.read_db().unwrap();
+        assert!(db_state.epics[&epic_1].stories.is_empty());
+        assert_eq!(db_state.epics[&epic_2].stories, vec![story_id]);
+
+        assert_eq!(nav.get_page_count(), 2);
+        let current_page = nav.get_current_page().unwrap();
+        let story_detail = current_page.as_any().downcast_ref::<StoryDetail>();
+        assert_eq!(story_detail.unwrap().epic_id, epic_2);
+    }
+
+    #[test]
+    fn handle_action_should_handle_update_story_priority() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let mut prompts = Prompts::new();
+        prompts.update_priority = Box::new(|| Some(Priority::High));
+
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::UpdateStoryPriority { story_id })
+            .unwrap();
+
+        let db_state = db.read_db().unwrap();
+        assert_eq!(db_state.stories[&story_id].priority, Priority::High);
+    }
+
+    #[test]
+    fn handle_action_should_handle_assign_and_unassign_story() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        db.add_user(User::new("alice".to_owned(), "Alice".to_owned()))
+            .unwrap();
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let mut prompts = Prompts::new();
+        prompts.assign = Box::new(|users| Some(users[0].username.clone()));
+
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::AssignStory { story_id }).unwrap();
+        let story = db.get_story(story_id).unwrap().unwrap();
+        assert_eq!(story.assignee.as_deref(), Some("alice"));
+
+        nav.handle_action(Action::UnassignStory { story_id })
+            .unwrap();
+        let story = db.get_story(story_id).unwrap().unwrap();
+        assert_eq!(story.assignee, None);
+    }
+
+    #[test]
+    fn handle_action_should_navigate_to_my_work() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+
+        let mut nav = Navigator::new(db);
+        nav.current_user = Some("alice".to_owned());
+
+        nav.handle_action(Action::NavigateToMyWork).unwrap();
+        assert_eq!(nav.get_page_count(), 2);
+
+        let current_page = nav.get_current_page().unwrap();
+        let my_work = current_page.as_any().downcast_ref::<MyWork>();
+        assert_eq!(my_work.unwrap().user.as_deref(), Some("alice"));
+    }
+
+    #[test]
+    fn handle_action_should_open_search_results() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+
+        let mut nav = Navigator::new(db);
+
+        let mut prompts = Prompts::new();
+        prompts.search = Box::new(|| None);
+        nav.set_prompts(prompts);
+        nav.handle_action(Action::Search).unwrap();
+        assert_eq!(nav.get_page_count(), 1);
+
+        let mut prompts = Prompts::new();
+        prompts.search = Box::new(|| Some("login".to_owned()));
+        nav.set_prompts(prompts);
+        nav.handle_action(Action::Search).unwrap();
+        assert_eq!(nav.get_page_count(), 2);
+
+        let current_page = nav.get_current_page().unwrap();
+        let search_page = current_page.as_any().downcast_ref::<SearchPage>();
+        assert_eq!(search_page.unwrap().query, "login");
+    }
+
+    #[test]
+    fn handle_action_should_replace_page_when_sorting_stories() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+
+        let mut nav = Navigator::new(db);
+
+        nav.handle_action(Action::NavigateToEpicDetail { epic_id: 1 })
+            .unwrap();
+        nav.handle_action(Action::SortStories {
+            epic_id: 1,
+            by_priority: true,
+            label_filter: None,
+            query: None,
+        })
+        .unwrap();
+
+        assert_eq!(nav.get_page_count(), 2);
+        let current_page = nav.get_current_page().unwrap();
+        let epic_detail = current_page.as_any().downcast_ref::<EpicDetail>();
+        assert!(epic_detail.unwrap().sort_by_priority);
+    }
+
+    #[test]
+    fn handle_action_should_filter_stories_by_label() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+
+        let mut nav = Navigator::new(db);
+
+        let mut prompts = Prompts::new();
+        prompts.filter_label = Box::new(|| Some("frontend".to_owned()));
+
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::NavigateToEpicDetail { epic_id: 1 })
+            .unwrap();
+        nav.handle_action(Action::FilterStoriesByLabel {
+            epic_id: 1,
+            sort_by_priority: true,
+            query: None,
+        })
+        .unwrap();
+
+        assert_eq!(nav.get_page_count(), 2);
+        let current_page = nav.get_current_page().unwrap();
+        let epic_detail = current_page.as_any().downcast_ref::<EpicDetail>().unwrap();
+        assert!(epic_detail.sort_by_priority);
+        assert_eq!(epic_detail.label_filter.as_deref(), Some("frontend"));
+    }
+
+    #[test]
+    fn handle_action_should_filter_by_query_and_keep_label_filter() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+
+        let mut nav = Navigator::new(db);
+
+        let mut prompts = Prompts::new();
+        prompts.filter_query = Box::new(|kind| Filter::parse("status:open", kind).ok());
+
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::FilterEpicsByQuery {
+            label_filter: Some("frontend".to_owned()),
+        })
+        .unwrap();
+        assert_eq!(nav.get_page_count(), 1);
+        let current_page = nav.get_current_page().unwrap();
+        let home_page = current_page.as_any().downcast_ref::<HomePage>().unwrap();
+        assert_eq!(home_page.label_filter.as_deref(), Some("frontend"));
+        assert_eq!(home_page.query.as_ref().unwrap().to_string(), "status:open");
+
+        nav.handle_action(Action::NavigateToEpicDetail { epic_id: 1 })
+            .unwrap();
+        nav.handle_action(Action::FilterStoriesByQuery {
+            epic_id: 1,
+            sort_by_priority: true,
+            label_filter: None,
+        })
+        .unwrap();
+        let current_page = nav.get_current_page().unwrap();
+        let epic_detail = current_page.as_any().downcast_ref::<EpicDetail>().unwrap();
+        assert!(epic_detail.sort_by_priority);
+        assert!(epic_detail.query.is_some());
+    }
+
+    #[test]
+    fn handle_action_should_handle_add_and_remove_story_label() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let mut prompts = Prompts::new();
+        prompts.label = Box::new(|| Some("tech-debt".to_owned()));
+
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::AddStoryLabel { story_id })
+            .unwrap();
+        let story = db.get_story(story_id).unwrap().unwrap();
+        assert!(story.labels.contains("tech-debt"));
+
+        nav.handle_action(Action::RemoveStoryLabel { story_id })
+            .unwrap();
+        let story = db.get_story(story_id).unwrap().unwrap();
+        assert!(story.labels.is_empty());
+    }
+
+    #[test]
+    fn handle_action_should_handle_add_comment_and_paging() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let mut prompts = Prompts::new();
+        prompts.add_comment = Box::new(|| Some("looks good".to_owned()));
+
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::AddComment { story_id }).unwrap();
+        let story = db.get_story(story_id).unwrap().unwrap();
+        assert_eq!(story.comments[0].body, "looks good");
+
+        nav.handle_action(Action::NavigateToStoryDetail { epic_id, story_id })
+            .unwrap();
+        nav.handle_action(Action::PageComments {
+            epic_id,
+            story_id,
+            page: 1,
+        })
+        .unwrap();
+        assert_eq!(nav.get_page_count(), 2);
+        let story_detail = nav.get_current_page().unwrap().as_any();
+        let story_detail = story_detail.downcast_ref::<StoryDetail>().unwrap();
+        assert_eq!(story_detail.comment_page, 1);
+    }
+
+    #[test]
+    fn handle_action_should_handle_delete_story() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
         let epic_id = db
             .create_epic(Epic::new("".to_owned(), "".to_owned()))
             .unwrap();
@@ -363,4 +978,133 @@ mod tests {
         let db_state = db.read_db().unwrap();
         assert_eq!(db_state.stories.len(), 0);
     }
+
+    #[test]
+    fn handle_action_should_handle_undo_and_redo() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let mut prompts = Prompts::new();
+        prompts.delete_epic = Box::new(|| true);
+
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::DeleteEpic { epic_id }).unwrap();
+        nav.handle_action(Action::Undo).unwrap();
+        assert_eq!(db.read_db().unwrap().epics.len(), 1);
+
+        nav.handle_action(Action::Redo).unwrap();
+        assert_eq!(db.read_db().unwrap().epics.len(), 0);
+    }
+
+    #[test]
+    fn handle_action_should_select_and_move_cards_on_board() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        nav.handle_action(Action::NavigateToBoard {
+            epic_id: Some(epic_id),
+        })
+        .unwrap();
+        nav.handle_action(Action::SelectCard {
+            epic_id: Some(epic_id),
+            story_id,
+        })
+        .unwrap();
+        assert_eq!(nav.get_page_count(), 2);
+
+        let current_page = nav.get_current_page().unwrap();
+        let board_page = current_page.as_any().downcast_ref::<BoardPage>().unwrap();
+        assert_eq!(board_page.epic_id, Some(epic_id));
+        assert_eq!(board_page.selected, Some(story_id));
+
+        nav.handle_action(Action::MoveCard {
+            story_id,
+            status: Status::InProgress,
+        })
+        .unwrap();
+        assert_eq!(
+            db.get_story(story_id).unwrap().unwrap().status,
+            Status::InProgress
+        );
+    }
+
+    #[test]
+    fn handle_action_should_offer_allowed_next_statuses() {
+        let mut db = JiraDatabase::with_database(Box::new(MockDB::new()));
+        db.workflow = Workflow {
+            statuses: vec![Status::Open, Status::InProgress, Status::Closed],
+            transitions: HashMap::from([(Status::Open, vec![Status::InProgress])]),
+            ..Workflow::default()
+        };
+        let db = Rc::new(db);
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let mut prompts = Prompts::new();
+        prompts.update_status = Box::new(|statuses| {
+            assert_eq!(statuses, [Status::InProgress]);
+            statuses.first().cloned()
+        });
+
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::UpdateEpicStatus { epic_id })
+            .unwrap();
+        assert_eq!(
+            db.get_epic(epic_id).unwrap().unwrap().status,
+            Status::InProgress
+        );
+    }
+
+    #[test]
+    fn handle_action_should_confirm_closing_epic_with_open_stories() {
+        let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+        let epic_id = db
+            .create_epic(Epic::new("".to_owned(), "".to_owned()))
+            .unwrap();
+        let story_id = db
+            .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+            .unwrap();
+
+        let mut nav = Navigator::new(Rc::clone(&db));
+
+        let mut prompts = Prompts::new();
+        prompts.update_status = Box::new(|_| Some(Status::Closed));
+        prompts.close_epic = Box::new(move |open_stories| {
+            assert_eq!(open_stories, [story_id]);
+            false
+        });
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::UpdateEpicStatus { epic_id })
+            .unwrap();
+        assert_eq!(db.get_epic(epic_id).unwrap().unwrap().status, Status::Open);
+
+        let mut prompts = Prompts::new();
+        prompts.update_status = Box::new(|_| Some(Status::Closed));
+        prompts.close_epic = Box::new(|_| true);
+        nav.set_prompts(prompts);
+
+        nav.handle_action(Action::UpdateEpicStatus { epic_id })
+            .unwrap();
+        assert_eq!(
+            db.get_epic(epic_id).unwrap().unwrap().status,
+            Status::Closed
+        );
+    }
 }
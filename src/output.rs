@@ -0,0 +1,199 @@
+use std::io::Write;
+
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::models::{Epic, Story, User};
+use crate::ui::pages::page_helpers::{get_column_string, get_user_string};
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human readable table, long values are truncated
+    Table,
+    /// A single JSON array
+    Json,
+    /// One JSON object per line
+    Ndjson,
+}
+
+/// Stable serialized form of an epic: its id followed by all `Epic` fields.
+#[derive(Serialize, Debug)]
+pub struct EpicRecord<'a> {
+    pub id: u32,
+    #[serde(flatten)]
+    pub epic: &'a Epic,
+}
+
+/// Stable serialized form of a story: its id and owning epic id followed by all `Story` fields.
+#[derive(Serialize, Debug)]
+pub struct StoryRecord<'a> {
+    pub id: u32,
+    pub epic_id: u32,
+    #[serde(flatten)]
+    pub story: &'a Story,
+}
+
+pub fn write_epics(out: &mut dyn Write, format: OutputFormat, epics: &[EpicRecord]) -> Result<()> {
+    match format {
+        OutputFormat::Table => {
+            writeln!(
+                out,
+                "     id     |               name               |      status      |  priority  |    owner    "
+            )?;
+            for record in epics {
+                let id_col = get_column_string(&record.id.to_string(), 11);
+                let name_col = get_column_string(&record.epic.name, 32);
+                let status_col = get_column_string(&record.epic.status.to_string(), 16);
+                let priority_col = get_column_string(&record.epic.priority.to_string(), 10);
+                writeln!(
+                    out,
+                    "{} | {} | {} | {} | {}",
+                    id_col,
+                    name_col,
+                    status_col,
+                    priority_col,
+                    get_user_string(record.epic.owner.as_deref())
+                )?;
+            }
+            Ok(())
+        }
+        _ => write_records(out, format, epics),
+    }
+}
+
+pub fn write_stories(
+    out: &mut dyn Write,
+    format: OutputFormat,
+    stories: &[StoryRecord],
+) -> Result<()> {
+    match format {
+        OutputFormat::Table => {
+            writeln!(
+                out,
+                "     id     |               name               |      status      |  priority  |  assignee  "
+            )?;
+            for record in stories {
+                let id_col = get_column_string(&record.id.to_string(), 11);
+                let name_col = get_column_string(&record.story.name, 32);
+                let status_col = get_column_string(&record.story.status.to_string(), 16);
+                let priority_col = get_column_string(&record.story.priority.to_string(), 10);
+                writeln!(
+                    out,
+                    "{} | {} | {} | {} | {}",
+                    id_col,
+                    name_col,
+                    status_col,
+                    priority_col,
+                    get_user_string(record.story.assignee.as_deref())
+                )?;
+            }
+            Ok(())
+        }
+        _ => write_records(out, format, stories),
+    }
+}
+
+pub fn write_users(out: &mut dyn Write, format: OutputFormat, users: &[User]) -> Result<()> {
+    match format {
+        OutputFormat::Table => {
+            writeln!(out, "     username     |               name               ")?;
+            for user in users {
+                let username_col = get_column_string(&user.username, 17);
+                writeln!(out, "{} | {}", username_col, user.name)?;
+            }
+            Ok(())
+        }
+        _ => write_records(out, format, users),
+    }
+}
+
+fn write_records<T: Serialize>(
+    out: &mut dyn Write,
+    format: OutputFormat,
+    records: &[T],
+) -> Result<()> {
+    match format {
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut *out, records)?;
+            writeln!(out)?;
+        }
+        OutputFormat::Ndjson => {
+            for record in records {
+                serde_json::to_writer(&mut *out, record)?;
+                writeln!(out)?;
+            }
+        }
+        OutputFormat::Table => unreachable!("tables are written by the caller"),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    #[test]
+    fn json_should_contain_id_and_all_fields() {
+        let mut epic = Epic::new("name".to_owned(), "description".to_owned());
+        epic.stories.push(2);
+        let records = [EpicRecord { id: 1, epic: &epic }];
+
+        let mut out = Vec::new();
+        write_epics(&mut out, OutputFormat::Json, &records).unwrap();
+
+        let value: Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(
+            value,
+            json!([{
+                "id": 1,
+                "name": "name",
+                "description": "description",
+                "status": "Open",
+                "priority": "Medium",
+                "owner": null,
+                "labels": [],
+                "created": null,
+                "updated": null,
+                "jira_key": null,
+                "stories": [2]
+            }])
+        );
+    }
+
+    #[test]
+    fn ndjson_should_write_one_object_per_line() {
+        let story_1 = Story::new(
+            "a very long story name that would be truncated in a table".to_owned(),
+            "".to_owned(),
+        );
+        let story_2 = Story::new("b".to_owned(), "".to_owned());
+        let records = [
+            StoryRecord {
+                id: 2,
+                epic_id: 1,
+                story: &story_1,
+            },
+            StoryRecord {
+                id: 3,
+                epic_id: 1,
+                story: &story_2,
+            },
+        ];
+
+        let mut out = Vec::new();
+        write_stories(&mut out, OutputFormat::Ndjson, &records).unwrap();
+
+        let lines: Vec<Value> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["id"], 2);
+        assert_eq!(lines[0]["epic_id"], 1);
+        assert_eq!(lines[0]["name"], story_1.name.as_str());
+        assert_eq!(lines[1]["status"], "Open");
+    }
+}
@@ -0,0 +1,142 @@
+use std::io::Write;
+
+use anyhow::Result;
+
+use crate::models::{DBState, Status};
+
+/// Writes every epic as a heading with its description and a checklist of its stories, in id
+/// order. Stories count as done when resolved or closed. Names are escaped, descriptions are
+/// written as is since they may contain Markdown themselves.
+///
+/// With `statuses` given, only stories in one of them are listed and epics without such stories
+/// are left out.
+pub fn write_markdown(db_state: &DBState, statuses: &[Status], out: &mut dyn Write) -> Result<()> {
+    let mut epic_ids: Vec<&u32> = db_state.epics.keys().collect();
+    epic_ids.sort();
+    let mut first = true;
+    for epic_id in epic_ids {
+        let epic = &db_state.epics[epic_id];
+        let stories: Vec<_> = epic
+            .stories
+            .iter()
+            .filter_map(|story_id| Some((story_id, db_state.stories.get(story_id)?)))
+            .filter(|(_, story)| statuses.is_empty() || statuses.contains(&story.status))
+            .collect();
+        if !statuses.is_empty() && stories.is_empty() {
+            continue;
+        }
+        if !first {
+            writeln!(out)?;
+        }
+        first = false;
+        writeln!(out, "## {} (#{})", escape(&epic.name), epic_id)?;
+        writeln!(out)?;
+        writeln!(out, "Status: {:?}", epic.status)?;
+        if !epic.description.trim().is_empty() {
+            writeln!(out)?;
+            writeln!(out, "{}", epic.description.trim_end())?;
+        }
+        if stories.is_empty() {
+            continue;
+        }
+        writeln!(out)?;
+        for (story_id, story) in stories {
+            let done = matches!(story.status, Status::Resolved | Status::Closed);
+            writeln!(
+                out,
+                "- [{}] {} (#{}, {:?})",
+                if done { "x" } else { " " },
+                escape(&story.name),
+                story_id,
+                story.status
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Backslash-escapes the characters that would turn a name into Markdown syntax.
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '#') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Epic, Story};
+    use std::collections::HashMap;
+
+    fn db_state() -> DBState {
+        let mut login = Epic::new("Login".to_owned(), "User login flow\n\n".to_owned());
+        login.status = Status::InProgress;
+        login.stories = vec![3, 4];
+        let mut form = Story::new("Login form".to_owned(), "".to_owned());
+        form.status = Status::Resolved;
+        DBState {
+            last_item_id: 4,
+            epics: HashMap::from([
+                (1, login),
+                (2, Epic::new("Billing".to_owned(), "".to_owned())),
+            ]),
+            stories: HashMap::from([
+                (3, form),
+                (4, Story::new("Password reset".to_owned(), "".to_owned())),
+            ]),
+            users: vec![],
+        }
+    }
+
+    fn markdown(statuses: &[Status]) -> String {
+        let mut out = vec![];
+        write_markdown(&db_state(), statuses, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn write_markdown_should_check_off_finished_stories() {
+        assert_eq!(
+            markdown(&[]),
+            "## Login (#1)\n\
+             \n\
+             Status: InProgress\n\
+             \n\
+             User login flow\n\
+             \n\
+             - [x] Login form (#3, Resolved)\n\
+             - [ ] Password reset (#4, Open)\n\
+             \n\
+             ## Billing (#2)\n\
+             \n\
+             Status: Open\n"
+        );
+    }
+
+    #[test]
+    fn write_markdown_should_only_list_stories_with_given_statuses() {
+        assert_eq!(
+            markdown(&[Status::Open, Status::InProgress]),
+            "## Login (#1)\n\
+             \n\
+             Status: InProgress\n\
+             \n\
+             User login flow\n\
+             \n\
+             - [ ] Password reset (#4, Open)\n"
+        );
+        assert_eq!(markdown(&[Status::Closed]), "");
+    }
+
+    #[test]
+    fn escape_should_escape_markdown_characters() {
+        assert_eq!(escape("Fix [x] parsing"), r"Fix \[x\] parsing");
+        assert_eq!(escape("#1 crash"), r"\#1 crash");
+        assert_eq!(escape(r"a\b `c` *d* _e_"), r"a\\b \`c\` \*d\* \_e\_");
+    }
+}
@@ -0,0 +1,188 @@
+use itertools::Itertools;
+
+use std::collections::HashMap;
+
+use crate::models::{Epic, ItemKind, Story};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchResult {
+    pub kind: ItemKind,
+    pub id: u32,
+    /// The epic itself for epics, the epic containing the story for stories.
+    pub epic_id: u32,
+    pub name: String,
+    pub score: u32,
+    /// Fields that matched at least one word of the query, in the order of their weights.
+    pub fields: Vec<&'static str>,
+}
+
+/// How much a word found in a field adds to the score, names count most.
+const NAME_WEIGHT: u32 = 8;
+const LABEL_WEIGHT: u32 = 4;
+const DESCRIPTION_WEIGHT: u32 = 2;
+const COMMENT_WEIGHT: u32 = 1;
+
+/// Finds the epics and stories containing every word of the query (ignoring case) in their name,
+/// description, labels or comments. Best matches come first, ties are ordered by id.
+pub fn search(
+    epics: &HashMap<u32, Epic>,
+    stories: &HashMap<u32, Story>,
+    query: &str,
+) -> Vec<SearchResult> {
+    let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    if words.is_empty() {
+        return vec![];
+    }
+    let mut results = vec![];
+    for (id, epic) in epics {
+        let fields = [
+            ("name", NAME_WEIGHT, vec![epic.name.as_str()]),
+            (
+                "labels",
+                LABEL_WEIGHT,
+                epic.labels.iter().map(String::as_str).collect(),
+            ),
+            (
+                "description",
+                DESCRIPTION_WEIGHT,
+                vec![epic.description.as_str()],
+            ),
+        ];
+        if let Some(result) = score(ItemKind::Epic, *id, *id, &epic.name, &fields, &words) {
+            results.push(result);
+        }
+        for story_id in &epic.stories {
+            let Some(story) = stories.get(story_id) else {
+                continue;
+            };
+            let fields = [
+                ("name", NAME_WEIGHT, vec![story.name.as_str()]),
+                (
+                    "labels",
+                    LABEL_WEIGHT,
+                    story.labels.iter().map(String::as_str).collect(),
+                ),
+                (
+                    "description",
+                    DESCRIPTION_WEIGHT,
+                    vec![story.description.as_str()],
+                ),
+                (
+                    "comments",
+                    COMMENT_WEIGHT,
+                    story.comments.iter().map(|c| c.body.as_str()).collect(),
+                ),
+            ];
+            if let Some(result) = score(
+                ItemKind::Story,
+                *story_id,
+                *id,
+                &story.name,
+                &fields,
+                &words,
+            ) {
+                results.push(result);
+            }
+        }
+    }
+    results
+        .into_iter()
+        .sorted_by_key(|result| (std::cmp::Reverse(result.score), result.id))
+        .collect()
+}
+
+/// Scores one item, `None` if any word of the query is missing from all of its fields.
+fn score(
+    kind: ItemKind,
+    id: u32,
+    epic_id: u32,
+    name: &str,
+    fields: &[(&'static str, u32, Vec<&str>)],
+    words: &[String],
+) -> Option<SearchResult> {
+    let mut total = 0;
+    let mut matched = vec![];
+    for word in words {
+        let mut found = false;
+        for (field, weight, texts) in fields {
+            let hits = texts
+                .iter()
+                .filter(|text| text.to_lowercase().contains(word.as_str()))
+                .count() as u32;
+            if hits > 0 {
+                found = true;
+                total += weight * hits;
+                if !matched.contains(field) {
+                    matched.push(*field);
+                }
+            }
+        }
+        if !found {
+            return None;
+        }
+    }
+    matched.sort_by_key(|field| fields.iter().position(|(name, _, _)| name == field));
+    Some(SearchResult {
+        kind,
+        id,
+        epic_id,
+        name: name.to_owned(),
+        score: total,
+        fields: matched,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Comment, DBState};
+    use chrono::Utc;
+
+    fn search_for(query: &str) -> Vec<SearchResult> {
+        let db_state = db_state();
+        search(&db_state.epics, &db_state.stories, query)
+    }
+
+    fn db_state() -> DBState {
+        let mut epic = Epic::new("Login".to_owned(), "User login flow".to_owned());
+        epic.stories = vec![2, 3];
+        let mut form = Story::new("Login form".to_owned(), "".to_owned());
+        form.labels.insert("frontend".to_owned());
+        let mut reset = Story::new("Password reset".to_owned(), "Mail a LOGIN link".to_owned());
+        reset.comments.push(Comment {
+            author: None,
+            time: Utc::now(),
+            body: "needs the frontend team".to_owned(),
+        });
+        DBState {
+            last_item_id: 3,
+            epics: HashMap::from([(1, epic)]),
+            stories: HashMap::from([(2, form), (3, reset)]),
+            users: vec![],
+        }
+    }
+
+    #[test]
+    fn search_should_rank_name_matches_first() {
+        let results = search_for("login");
+
+        let ids: Vec<u32> = results.iter().map(|result| result.id).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+        assert_eq!(results[0].fields, vec!["name", "description"]);
+        assert_eq!(results[1].kind, ItemKind::Story);
+        assert_eq!(results[2].epic_id, 1);
+        assert_eq!(results[2].fields, vec!["description"]);
+    }
+
+    #[test]
+    fn search_should_require_every_word() {
+        let results = search_for("Frontend   login");
+
+        let ids: Vec<u32> = results.iter().map(|result| result.id).collect();
+        assert_eq!(ids, vec![2, 3]);
+        assert_eq!(results[1].fields, vec!["description", "comments"]);
+
+        assert!(search_for("login missing").is_empty());
+        assert!(search_for("  ").is_empty());
+    }
+}
@@ -1,21 +1,30 @@
 use std::any::Any;
+use std::collections::HashMap;
 use std::rc::Rc;
 
 use anyhow::{anyhow, Result};
 use itertools::Itertools;
 
 use crate::db::JiraDatabase;
-use crate::models::Action;
-mod page_helpers;
+use crate::filter::Filter;
+use crate::io_utils::get_terminal_width;
+use crate::models::{Action, ItemKind, Story};
+use crate::search::search;
+pub mod page_helpers;
 use page_helpers::*;
 pub trait Page {
     fn draw_page(&self) -> Result<()>;
     fn handle_input(&self, input: &str) -> Result<Option<Action>>;
+    #[allow(dead_code)] //used in test
     fn as_any(&self) -> &dyn Any;
 }
 
 pub struct HomePage {
     pub db: Rc<JiraDatabase>,
+    /// Only list epics with this label.
+    pub label_filter: Option<String>,
+    /// Only list epics matching this filter expression.
+    pub query: Option<Filter>,
 }
 
 impl Page for HomePage {
@@ -23,17 +32,41 @@ impl Page for HomePage {
         self
     }
     fn draw_page(&self) -> Result<()> {
-        println!("----------------------------- EPICS -----------------------------");
-        println!("     id     |               name               |      status      ");
+        println!("------------------------------------ EPICS ------------------------------------");
+        println!(
+            "     id     |               name               |      status      |    owner    "
+        );
 
-        let epics = self.db.read_db()?.epics;
-        for key in epics.keys().sorted() {
+        let epics = self.db.list_epics()?;
+        for key in epics
+            .keys()
+            .filter(|key| has_label(&epics[key].labels, self.label_filter.as_deref()))
+            .filter(|key| {
+                self.query
+                    .as_ref()
+                    .is_none_or(|query| query.matches_epic(**key, &epics[key]))
+            })
+            .sorted()
+        {
             let id_col = get_column_string(&key.to_string(), 11);
             let name_col = get_column_string(&epics[key].name, 32);
-            println!("{} | {} | {}", id_col, name_col, epics[key].status); 
+            let status_col = get_column_string(&epics[key].status.to_string(), 16);
+            println!(
+                "{} | {} | {} | {}",
+                id_col,
+                name_col,
+                status_col,
+                get_user_string(epics[key].owner.as_deref())
+            );
+        }
+        if let Some(label) = &self.label_filter {
+            println!("\nShowing epics labeled {}", label);
+        }
+        if let Some(query) = &self.query {
+            println!("\nShowing epics matching {}", query);
         }
         println!("\n");
-        println!("[q] quit | [c] create epics | [:id:] navigate to epics");
+        println!("[q] quit | [c] create epics | [:id:] navigate to epics | [f] filter by label | [?] filter by query | [/] search | [b] board | [w] my work | [z] undo | [y] redo");
 
         Ok(())
     }
@@ -41,10 +74,20 @@ impl Page for HomePage {
         match input {
             "q" => Ok(Some(Action::Exit)),
             "c" => Ok(Some(Action::CreateEpic)),
+            "f" => Ok(Some(Action::FilterEpicsByLabel {
+                query: self.query.clone(),
+            })),
+            "?" => Ok(Some(Action::FilterEpicsByQuery {
+                label_filter: self.label_filter.clone(),
+            })),
+            "w" => Ok(Some(Action::NavigateToMyWork)),
+            "/" => Ok(Some(Action::Search)),
+            "b" => Ok(Some(Action::NavigateToBoard { epic_id: None })),
+            "z" => Ok(Some(Action::Undo)),
+            "y" => Ok(Some(Action::Redo)),
             x => {
-                let epics = self.db.read_db()?.epics;
                 if let Ok(id) = x.parse::<u32>() {
-                    if epics.contains_key(&id) {
+                    if self.db.get_epic(id)?.is_some() {
                         return Ok(Some(Action::NavigateToEpicDetail { epic_id: id }));
                     }
                 }
@@ -57,57 +100,150 @@ impl Page for HomePage {
 pub struct EpicDetail {
     pub epic_id: u32,
     pub db: Rc<JiraDatabase>,
+    /// List the stories from highest to lowest priority instead of in insertion order.
+    pub sort_by_priority: bool,
+    /// Only list stories with this label.
+    pub label_filter: Option<String>,
+    /// Only list stories matching this filter expression.
+    pub query: Option<Filter>,
 }
 impl Page for EpicDetail {
     fn as_any(&self) -> &dyn Any {
         self
     }
     fn draw_page(&self) -> Result<()> {
-        println!("------------------------------ EPIC ------------------------------");
-        println!("  id  |     name     |         description         |    status    ");
-        let db_state = self.db.read_db()?;
-        let epic = db_state
-            .epics
-            .get(&self.epic_id)
+        println!("------------------------------------------ EPIC ------------------------------------------");
+        println!("  id  |     name     |         description         |    status    |  priority  |    owner    ");
+        let epic = self
+            .db
+            .get_epic(self.epic_id)?
             .ok_or_else(|| anyhow!("could not find epic with id {}", &self.epic_id))?;
         let id_col = get_column_string(&self.epic_id.to_string(), 5);
         let name_col = get_column_string(&epic.name, 12);
-        let des_col = get_column_string(&epic.description, 27);
+        let des_col = get_column_string(get_first_line(&epic.description), 27);
+        let status_col = get_column_string(&epic.status.to_string(), 12);
+        let priority_col = get_column_string(&epic.priority.to_string(), 10);
         println!(
-            "{} | {} | {} | {}\n",
-            id_col, name_col, des_col, epic.status
+            "{} | {} | {} | {} | {} | {}\n",
+            id_col,
+            name_col,
+            des_col,
+            status_col,
+            priority_col,
+            get_user_string(epic.owner.as_deref())
+        );
+        if let Some(description) = get_description_block(&epic.description) {
+            println!("Description:\n{}\n", description);
+        }
+        if !epic.labels.is_empty() {
+            println!("Labels: {}\n", get_labels_string(&epic.labels));
+        }
+        println!(
+            "Created: {} | Updated: {}\n",
+            get_time_string(epic.created),
+            get_time_string(epic.updated)
         );
 
-        println!("---------------------------- STORIES ----------------------------");
-        println!("     id     |               name               |      status      ");
+        println!("----------------------------------------- STORIES -----------------------------------------");
+        println!("     id     |               name               |      status      |  priority  |  assignee  ");
 
-        let storys = db_state.stories;
-        for key in epic.stories.iter() {
-            //storys.keys().sorted() {
+        let storys = self.db.list_stories()?;
+        let filtered = epic
+            .stories
+            .iter()
+            .filter(|key| has_label(&storys[key].labels, self.label_filter.as_deref()))
+            .filter(|key| {
+                self.query
+                    .as_ref()
+                    .is_none_or(|query| query.matches_story(**key, self.epic_id, &storys[key]))
+            });
+        let story_ids: Vec<&u32> = if self.sort_by_priority {
+            // stable sort, stories of equal priority keep their insertion order
+            filtered.sorted_by_key(|key| storys[key].priority).collect()
+        } else {
+            filtered.collect()
+        };
+        for key in story_ids {
             let id_col = get_column_string(&key.to_string(), 11);
             let name_col = get_column_string(&storys[key].name, 32);
-            println!("{} | {} | {}", id_col, name_col, storys[key].status); 
+            let status_col = get_column_string(&storys[key].status.to_string(), 16);
+            let priority_col = get_column_string(&storys[key].priority.to_string(), 10);
+            println!(
+                "{} | {} | {} | {} | {}",
+                id_col,
+                name_col,
+                status_col,
+                priority_col,
+                get_user_string(storys[key].assignee.as_deref())
+            );
+        }
+        if let Some(label) = &self.label_filter {
+            println!("\nShowing stories labeled {}", label);
         }
+        if let Some(query) = &self.query {
+            println!("\nShowing stories matching {}", query);
+        }
+        println!();
+        draw_history(&self.db, self.epic_id)?;
         println!("\n");
-        println!("[p] previous | [u] update epic | [d] delete epic | [c] create story | [:id:] navigate to story");
+        println!("[p] previous | [e] edit epic | [u] update epic | [r] set priority | [a] assign owner | [x] unassign owner | [+] add label | [-] remove label | [d] delete epic | [c] create story | [:id:] navigate to story | [f] filter by label | [?] filter by query | [s] {} | [b] board | [z] undo | [y] redo",
+            if self.sort_by_priority { "sort by insertion order" } else { "sort by priority" });
         Ok(())
     }
     fn handle_input(&self, input: &str) -> Result<Option<Action>> {
         match input {
             "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "e" => Ok(Some(Action::UpdateEpic {
+                epic_id: self.epic_id,
+            })),
             "u" => Ok(Some(Action::UpdateEpicStatus {
                 epic_id: self.epic_id,
             })),
+            "r" => Ok(Some(Action::UpdateEpicPriority {
+                epic_id: self.epic_id,
+            })),
+            "a" => Ok(Some(Action::AssignEpic {
+                epic_id: self.epic_id,
+            })),
+            "x" => Ok(Some(Action::UnassignEpic {
+                epic_id: self.epic_id,
+            })),
+            "+" => Ok(Some(Action::AddEpicLabel {
+                epic_id: self.epic_id,
+            })),
+            "-" => Ok(Some(Action::RemoveEpicLabel {
+                epic_id: self.epic_id,
+            })),
+            "f" => Ok(Some(Action::FilterStoriesByLabel {
+                epic_id: self.epic_id,
+                sort_by_priority: self.sort_by_priority,
+                query: self.query.clone(),
+            })),
+            "?" => Ok(Some(Action::FilterStoriesByQuery {
+                epic_id: self.epic_id,
+                sort_by_priority: self.sort_by_priority,
+                label_filter: self.label_filter.clone(),
+            })),
             "d" => Ok(Some(Action::DeleteEpic {
                 epic_id: self.epic_id,
             })),
             "c" => Ok(Some(Action::CreateStory {
                 epic_id: self.epic_id,
             })),
+            "b" => Ok(Some(Action::NavigateToBoard {
+                epic_id: Some(self.epic_id),
+            })),
+            "s" => Ok(Some(Action::SortStories {
+                epic_id: self.epic_id,
+                by_priority: !self.sort_by_priority,
+                label_filter: self.label_filter.clone(),
+                query: self.query.clone(),
+            })),
+            "z" => Ok(Some(Action::Undo)),
+            "y" => Ok(Some(Action::Redo)),
             x => {
-                let stories = self.db.read_db()?.stories;
                 if let Ok(id) = x.parse::<u32>() {
-                    if stories.contains_key(&id) {
+                    if self.db.get_story(id)?.is_some() {
                         return Ok(Some(Action::NavigateToStoryDetail {
                             story_id: id,
                             epic_id: self.epic_id,
@@ -119,10 +255,33 @@ impl Page for EpicDetail {
         }
     }
 }
+/// Number of comments shown per page of a story's discussion.
+const COMMENTS_PER_PAGE: usize = 5;
+/// Number of change log entries shown on a detail page, the most recent ones.
+const HISTORY_LINES: usize = 10;
+fn draw_history(db: &JiraDatabase, item_id: u32) -> Result<()> {
+    println!("----------------------------------------- HISTORY -----------------------------------------");
+    let history = db.get_item_history(item_id)?;
+    let hidden = history.len().saturating_sub(HISTORY_LINES);
+    if hidden > 0 {
+        println!("({} earlier changes not shown)", hidden);
+    }
+    for change in &history[hidden..] {
+        println!(
+            "{} | {} | {}",
+            get_time_string(Some(change.time)),
+            get_column_string(get_user_string(change.actor.as_deref()), 10),
+            get_change_string(change)
+        );
+    }
+    Ok(())
+}
 pub struct StoryDetail {
     pub epic_id: u32,
     pub story_id: u32,
     pub db: Rc<JiraDatabase>,
+    /// Page of the comments shown, 0 for the most recent ones.
+    pub comment_page: usize,
 }
 
 impl Page for StoryDetail {
@@ -130,20 +289,57 @@ impl Page for StoryDetail {
         self
     }
     fn draw_page(&self) -> Result<()> {
-        println!("------------------------------ STORY ------------------------------");
-        println!("  id  |     name     |         description         |    status    ");
-        let db_state = self.db.read_db()?;
-        let story = db_state
-            .stories
-            .get(&self.story_id)
+        println!("------------------------------------------ STORY ------------------------------------------");
+        println!("  id  |     name     |         description         |    status    |  priority  |  assignee  ");
+        let story = self
+            .db
+            .get_story(self.story_id)?
             .ok_or_else(|| anyhow!("could not find story with id {}", &self.story_id))?;
 
         let id_col = get_column_string(&self.story_id.to_string(), 5);
         let name_col = get_column_string(&story.name, 12);
-        let des_col = get_column_string(&story.description, 27);
-        println!("{} | {} | {} | {}", id_col, name_col, des_col, story.status);
+        let des_col = get_column_string(get_first_line(&story.description), 27);
+        let status_col = get_column_string(&story.status.to_string(), 12);
+        let priority_col = get_column_string(&story.priority.to_string(), 10);
+        println!(
+            "{} | {} | {} | {} | {} | {}",
+            id_col,
+            name_col,
+            des_col,
+            status_col,
+            priority_col,
+            get_user_string(story.assignee.as_deref())
+        );
+        if let Some(description) = get_description_block(&story.description) {
+            println!("\nDescription:\n{}", description);
+        }
+        if !story.labels.is_empty() {
+            println!("\nLabels: {}", get_labels_string(&story.labels));
+        }
+
+        let pages = story.comments.len().div_ceil(COMMENTS_PER_PAGE).max(1);
+        println!(
+            "\n---------------------------------- COMMENTS (page {} of {}) ----------------------------------",
+            self.comment_page + 1,
+            pages
+        );
+        let range = get_latest_page(story.comments.len(), self.comment_page, COMMENTS_PER_PAGE);
+        for comment in &story.comments[range] {
+            println!(
+                "{} | {}\n{}",
+                get_time_string(Some(comment.time)),
+                get_user_string(comment.author.as_deref()),
+                get_indented(&comment.body)
+            );
+        }
+        println!(
+            "\nCreated: {} | Updated: {}\n",
+            get_time_string(story.created),
+            get_time_string(story.updated)
+        );
+        draw_history(&self.db, self.story_id)?;
         println!("\n");
-        println!("[p] previous | [u] update story | [d] delete story");
+        println!("[p] previous | [e] edit story | [u] update story | [r] set priority | [a] assign | [x] unassign | [+] add label | [-] remove label | [c] comment | [<] newer comments | [>] older comments | [d] delete story | [m] move story | [z] undo | [y] redo");
 
         Ok(())
     }
@@ -151,58 +347,395 @@ impl Page for StoryDetail {
     fn handle_input(&self, input: &str) -> Result<Option<Action>> {
         match input {
             "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "e" => Ok(Some(Action::UpdateStory {
+                story_id: self.story_id,
+            })),
             "u" => Ok(Some(Action::UpdateStoryStatus {
                 story_id: self.story_id,
             })),
+            "r" => Ok(Some(Action::UpdateStoryPriority {
+                story_id: self.story_id,
+            })),
+            "a" => Ok(Some(Action::AssignStory {
+                story_id: self.story_id,
+            })),
+            "x" => Ok(Some(Action::UnassignStory {
+                story_id: self.story_id,
+            })),
+            "+" => Ok(Some(Action::AddStoryLabel {
+                story_id: self.story_id,
+            })),
+            "-" => Ok(Some(Action::RemoveStoryLabel {
+                story_id: self.story_id,
+            })),
             "d" => Ok(Some(Action::DeleteStory {
                 epic_id: self.epic_id,
                 story_id: self.story_id,
             })),
+            "m" => Ok(Some(Action::MoveStory {
+                epic_id: self.epic_id,
+                story_id: self.story_id,
+            })),
+            "c" => Ok(Some(Action::AddComment {
+                story_id: self.story_id,
+            })),
+            "<" if self.comment_page > 0 => Ok(Some(Action::PageComments {
+                epic_id: self.epic_id,
+                story_id: self.story_id,
+                page: self.comment_page - 1,
+            })),
+            ">" => {
+                let comments = self
+                    .db
+                    .get_story(self.story_id)?
+                    .map_or(0, |story| story.comments.len());
+                if (self.comment_page + 1) * COMMENTS_PER_PAGE >= comments {
+                    return Ok(None);
+                }
+                Ok(Some(Action::PageComments {
+                    epic_id: self.epic_id,
+                    story_id: self.story_id,
+                    page: self.comment_page + 1,
+                }))
+            }
+            "z" => Ok(Some(Action::Undo)),
+            "y" => Ok(Some(Action::Redo)),
             _ => Ok(None),
         }
     }
 }
+/// Epics owned by and stories assigned to the current user.
+pub struct MyWork {
+    /// `None` if neither the config file nor `$USER` names a user.
+    pub user: Option<String>,
+    pub db: Rc<JiraDatabase>,
+}
+
+impl Page for MyWork {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn draw_page(&self) -> Result<()> {
+        println!(
+            "------------------------------------ MY WORK ------------------------------------"
+        );
+        let Some(user) = &self.user else {
+            println!("No current user, set \"user\" in the config file or $USER.");
+            println!("\n");
+            println!("[p] previous | [z] undo | [y] redo");
+            return Ok(());
+        };
+        println!("Epics owned by {}:", user);
+        println!("     id     |               name               |      status      ");
+        let epics = self.db.list_epics()?;
+        for key in epics.keys().sorted() {
+            let epic = &epics[key];
+            if epic.owner.as_deref() != Some(user.as_str()) {
+                continue;
+            }
+            let id_col = get_column_string(&key.to_string(), 11);
+            let name_col = get_column_string(&epic.name, 32);
+            println!("{} | {} | {}", id_col, name_col, epic.status);
+        }
+        println!("\nStories assigned to {}:", user);
+        println!("     id     |               name               |      status      |  priority  ");
+        let stories = self.db.list_stories()?;
+        let stories = stories
+            .iter()
+            .filter(|(_, story)| story.assignee.as_deref() == Some(user.as_str()))
+            .sorted_by_key(|(id, story)| (story.priority, **id));
+        for (key, story) in stories {
+            let id_col = get_column_string(&key.to_string(), 11);
+            let name_col = get_column_string(&story.name, 32);
+            let status_col = get_column_string(&story.status.to_string(), 16);
+            println!(
+                "{} | {} | {} | {}",
+                id_col, name_col, status_col, story.priority
+            );
+        }
+        println!("\n");
+        println!("[p] previous | [:id:] navigate to epic or story | [z] undo | [y] redo");
+        Ok(())
+    }
+    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
+        match input {
+            "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "z" => Ok(Some(Action::Undo)),
+            "y" => Ok(Some(Action::Redo)),
+            x => {
+                if let Ok(id) = x.parse::<u32>() {
+                    let epics = self.db.list_epics()?;
+                    if epics.contains_key(&id) {
+                        return Ok(Some(Action::NavigateToEpicDetail { epic_id: id }));
+                    }
+                    if let Some((epic_id, _)) =
+                        epics.iter().find(|(_, epic)| epic.stories.contains(&id))
+                    {
+                        return Ok(Some(Action::NavigateToStoryDetail {
+                            epic_id: *epic_id,
+                            story_id: id,
+                        }));
+                    }
+                }
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// Stories laid out in one column per status of the workflow.
+pub struct BoardPage {
+    /// Only show the stories of this epic, all stories if `None`.
+    pub epic_id: Option<u32>,
+    pub db: Rc<JiraDatabase>,
+    /// Story whose card is moved by the left/right keys.
+    pub selected: Option<u32>,
+}
+
+impl BoardPage {
+    /// Every card on the board as `(epic id, story id, story)`, highest priority first.
+    fn cards(&self) -> Result<Vec<(u32, u32, Story)>> {
+        let epics = match self.epic_id {
+            Some(epic_id) => {
+                let epic = self
+                    .db
+                    .get_epic(epic_id)?
+                    .ok_or_else(|| anyhow!("could not find epic with id {}", epic_id))?;
+                HashMap::from([(epic_id, epic)])
+            }
+            None => self.db.list_epics()?,
+        };
+        let mut stories = self.db.list_stories()?;
+        Ok(epics
+            .iter()
+            .flat_map(|(id, epic)| epic.stories.iter().map(|story_id| (*id, *story_id)))
+            .filter_map(|(epic_id, story_id)| {
+                let story = stories.remove(&story_id)?;
+                Some((epic_id, story_id, story))
+            })
+            .sorted_by_key(|(_, story_id, story)| (story.priority, *story_id))
+            .collect())
+    }
+    fn selected_card(&self) -> Result<Option<(u32, u32, Story)>> {
+        Ok(self
+            .cards()?
+            .into_iter()
+            .find(|(_, story_id, _)| Some(*story_id) == self.selected))
+    }
+    /// Moves the selected card one column to the left (`-1`) or right (`1`), no action at the edges
+    /// or if the workflow does not allow the status change.
+    fn move_selected(&self, step: isize) -> Result<Option<Action>> {
+        let Some((_, story_id, story)) = self.selected_card()? else {
+            return Ok(None);
+        };
+        let workflow = &self.db.workflow;
+        Ok(workflow
+            .statuses
+            .iter()
+            .position(|status| *status == story.status)
+            .and_then(|column| column.checked_add_signed(step))
+            .and_then(|column| workflow.statuses.get(column))
+            .filter(|status| workflow.check_transition(&story.status, status).is_ok())
+            .map(|status| Action::MoveCard {
+                story_id,
+                status: status.clone(),
+            }))
+    }
+}
+
+impl Page for BoardPage {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn draw_page(&self) -> Result<()> {
+        let cards = self.cards()?;
+        let title = match self.epic_id {
+            Some(epic_id) => format!(" BOARD OF EPIC {} ", epic_id),
+            None => " BOARD ".to_owned(),
+        };
+        let width = get_terminal_width();
+        let statuses = &self.db.workflow.statuses;
+        let column_width = get_board_column_width(width, statuses.len());
+        println!("{:-^width$}", title, width = width);
+        let header: Vec<String> = statuses
+            .iter()
+            .map(|status| get_column_string(&format!("{:?}", status), column_width))
+            .collect();
+        println!("{}", header.join(" | "));
+
+        let columns: Vec<Vec<String>> = statuses
+            .iter()
+            .map(|status| {
+                cards
+                    .iter()
+                    .filter(|(_, _, story)| story.status == *status)
+                    .map(|(_, story_id, story)| {
+                        let marker = if self.selected == Some(*story_id) {
+                            ">"
+                        } else {
+                            " "
+                        };
+                        format!("{}{} {}", marker, story_id, story.name)
+                    })
+                    .collect()
+            })
+            .collect();
+        let rows = columns.iter().map(Vec::len).max().unwrap_or(0);
+        for row in 0..rows {
+            let cells: Vec<String> = columns
+                .iter()
+                .map(|column| {
+                    get_column_string(column.get(row).map_or("", String::as_str), column_width)
+                })
+                .collect();
+            println!("{}", cells.join(" | ").trim_end());
+        }
+
+        println!("\n");
+        println!("[p] previous | [:id:] select card | [h] move left | [l] move right | [o] open card | [z] undo | [y] redo");
+
+        Ok(())
+    }
+    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
+        match input {
+            "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "h" => self.move_selected(-1),
+            "l" => self.move_selected(1),
+            "o" => Ok(self
+                .selected_card()?
+                .map(|(epic_id, story_id, _)| Action::NavigateToStoryDetail { epic_id, story_id })),
+            "z" => Ok(Some(Action::Undo)),
+            "y" => Ok(Some(Action::Redo)),
+            x => {
+                if let Ok(id) = x.parse::<u32>() {
+                    if self.cards()?.iter().any(|(_, story_id, _)| *story_id == id) {
+                        return Ok(Some(Action::SelectCard {
+                            epic_id: self.epic_id,
+                            story_id: id,
+                        }));
+                    }
+                }
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// Epics and stories matching a search query, best match first.
+pub struct SearchPage {
+    pub query: String,
+    pub db: Rc<JiraDatabase>,
+}
+
+impl Page for SearchPage {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn draw_page(&self) -> Result<()> {
+        println!(
+            "------------------------------------ SEARCH ------------------------------------"
+        );
+        println!("Results for \"{}\":", self.query);
+        println!("     id     |  type  |               name               |     matched in     ");
+        let results = search(
+            &self.db.list_epics()?,
+            &self.db.list_stories()?,
+            &self.query,
+        );
+        for result in &results {
+            let id_col = get_column_string(&result.id.to_string(), 11);
+            let kind_col = get_column_string(&format!("{:?}", result.kind), 6);
+            let name_col = get_column_string(&result.name, 32);
+            println!(
+                "{} | {} | {} | {}",
+                id_col,
+                kind_col,
+                name_col,
+                result.fields.join(", ")
+            );
+        }
+        if results.is_empty() {
+            println!("No epics or stories found.");
+        }
+        println!("\n");
+        println!("[p] previous | [:id:] navigate to epic or story | [z] undo | [y] redo");
+        Ok(())
+    }
+    fn handle_input(&self, input: &str) -> Result<Option<Action>> {
+        match input {
+            "p" => Ok(Some(Action::NavigateToPreviousPage)),
+            "z" => Ok(Some(Action::Undo)),
+            "y" => Ok(Some(Action::Redo)),
+            x => {
+                let Ok(id) = x.parse::<u32>() else {
+                    return Ok(None);
+                };
+                let result = search(
+                    &self.db.list_epics()?,
+                    &self.db.list_stories()?,
+                    &self.query,
+                )
+                .into_iter()
+                .find(|result| result.id == id);
+                Ok(result.map(|result| match result.kind {
+                    ItemKind::Epic => Action::NavigateToEpicDetail { epic_id: id },
+                    ItemKind::Story => Action::NavigateToStoryDetail {
+                        epic_id: result.epic_id,
+                        story_id: id,
+                    },
+                }))
+            }
+        }
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::db::test_utils::MockDB;
-    use crate::models::{Epic, Story};
+    use crate::models::{Epic, Priority, Status, Story};
+    use crate::workflow::Workflow;
 
     mod home_page {
         use super::*;
 
         #[test]
         fn draw_page_should_not_throw_error() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
 
-            let page = HomePage { db };
-            assert_eq!(page.draw_page().is_ok(), true);
+            let page = HomePage {
+                db,
+                label_filter: None,
+                query: None,
+            };
+            assert!(page.draw_page().is_ok());
         }
 
         #[test]
         fn handle_input_should_not_throw_error() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
 
-            let page = HomePage { db };
-            assert_eq!(page.handle_input("").is_ok(), true);
+            let page = HomePage {
+                db,
+                label_filter: None,
+                query: None,
+            };
+            assert!(page.handle_input("").is_ok());
         }
 
         #[test]
         fn handle_input_should_return_the_correct_actions() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
 
             let epic = Epic::new("".to_owned(), "".to_owned());
 
             let epic_id = db.create_epic(epic).unwrap();
 
-            let page = HomePage { db };
+            let page = HomePage {
+                db,
+                label_filter: None,
+                query: None,
+            };
 
             let q = "q";
             let c = "c";
@@ -214,6 +747,25 @@ mod tests {
 
             assert_eq!(page.handle_input(q).unwrap(), Some(Action::Exit));
             assert_eq!(page.handle_input(c).unwrap(), Some(Action::CreateEpic));
+            assert_eq!(
+                page.handle_input("w").unwrap(),
+                Some(Action::NavigateToMyWork)
+            );
+            assert_eq!(page.handle_input("/").unwrap(), Some(Action::Search));
+            assert_eq!(
+                page.handle_input("b").unwrap(),
+                Some(Action::NavigateToBoard { epic_id: None })
+            );
+            assert_eq!(
+                page.handle_input("?").unwrap(),
+                Some(Action::FilterEpicsByQuery { label_filter: None })
+            );
+            assert_eq!(
+                page.handle_input("f").unwrap(),
+                Some(Action::FilterEpicsByLabel { query: None })
+            );
+            assert_eq!(page.handle_input("z").unwrap(), Some(Action::Undo));
+            assert_eq!(page.handle_input("y").unwrap(), Some(Action::Redo));
             assert_eq!(
                 page.handle_input(&valid_epic_id).unwrap(),
                 Some(Action::NavigateToEpicDetail { epic_id: 1 })
@@ -236,45 +788,77 @@ mod tests {
 
         #[test]
         fn draw_page_should_not_throw_error() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+
+            let page = EpicDetail {
+                epic_id,
+                db,
+                sort_by_priority: false,
+                label_filter: None,
+                query: None,
+            };
+            assert!(page.draw_page().is_ok());
+        }
+
+        #[test]
+        fn draw_page_should_not_throw_error_when_sorted_by_priority() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
             let epic_id = db
                 .create_epic(Epic::new("".to_owned(), "".to_owned()))
                 .unwrap();
+            let story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            db.update_story_priority(story_id, Priority::Highest)
+                .unwrap();
 
-            let page = EpicDetail { epic_id, db };
-            assert_eq!(page.draw_page().is_ok(), true);
+            let page = EpicDetail {
+                epic_id,
+                db,
+                sort_by_priority: true,
+                label_filter: Some("frontend".to_owned()),
+                query: None,
+            };
+            assert!(page.draw_page().is_ok());
         }
 
         #[test]
         fn handle_input_should_not_throw_error() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
             let epic_id = db
                 .create_epic(Epic::new("".to_owned(), "".to_owned()))
                 .unwrap();
 
-            let page = EpicDetail { epic_id, db };
-            assert_eq!(page.handle_input("").is_ok(), true);
+            let page = EpicDetail {
+                epic_id,
+                db,
+                sort_by_priority: false,
+                label_filter: None,
+                query: None,
+            };
+            assert!(page.handle_input("").is_ok());
         }
 
         #[test]
         fn draw_page_should_throw_error_for_invalid_epic_id() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
 
-            let page = EpicDetail { epic_id: 999, db };
-            assert_eq!(page.draw_page().is_err(), true);
+            let page = EpicDetail {
+                epic_id: 999,
+                db,
+                sort_by_priority: false,
+                label_filter: None,
+                query: None,
+            };
+            assert!(page.draw_page().is_err());
         }
 
         #[test]
         fn handle_input_should_return_the_correct_actions() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
 
             let epic_id = db
                 .create_epic(Epic::new("".to_owned(), "".to_owned()))
@@ -283,7 +867,13 @@ mod tests {
                 .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
                 .unwrap();
 
-            let page = EpicDetail { epic_id, db };
+            let page = EpicDetail {
+                epic_id,
+                db,
+                sort_by_priority: false,
+                label_filter: None,
+                query: None,
+            };
 
             let p = "p";
             let u = "u";
@@ -298,18 +888,73 @@ mod tests {
                 page.handle_input(p).unwrap(),
                 Some(Action::NavigateToPreviousPage)
             );
+            assert_eq!(
+                page.handle_input("e").unwrap(),
+                Some(Action::UpdateEpic { epic_id: 1 })
+            );
             assert_eq!(
                 page.handle_input(u).unwrap(),
                 Some(Action::UpdateEpicStatus { epic_id: 1 })
             );
+            assert_eq!(
+                page.handle_input("r").unwrap(),
+                Some(Action::UpdateEpicPriority { epic_id: 1 })
+            );
+            assert_eq!(
+                page.handle_input("a").unwrap(),
+                Some(Action::AssignEpic { epic_id: 1 })
+            );
+            assert_eq!(
+                page.handle_input("x").unwrap(),
+                Some(Action::UnassignEpic { epic_id: 1 })
+            );
             assert_eq!(
                 page.handle_input(d).unwrap(),
                 Some(Action::DeleteEpic { epic_id: 1 })
             );
+            assert_eq!(
+                page.handle_input("b").unwrap(),
+                Some(Action::NavigateToBoard { epic_id: Some(1) })
+            );
+            assert_eq!(
+                page.handle_input("s").unwrap(),
+                Some(Action::SortStories {
+                    epic_id: 1,
+                    by_priority: true,
+                    label_filter: None,
+                    query: None,
+                })
+            );
+            assert_eq!(
+                page.handle_input("f").unwrap(),
+                Some(Action::FilterStoriesByLabel {
+                    epic_id: 1,
+                    sort_by_priority: false,
+                    query: None,
+                })
+            );
+            assert_eq!(
+                page.handle_input("?").unwrap(),
+                Some(Action::FilterStoriesByQuery {
+                    epic_id: 1,
+                    sort_by_priority: false,
+                    label_filter: None,
+                })
+            );
+            assert_eq!(
+                page.handle_input("+").unwrap(),
+                Some(Action::AddEpicLabel { epic_id: 1 })
+            );
+            assert_eq!(
+                page.handle_input("-").unwrap(),
+                Some(Action::RemoveEpicLabel { epic_id: 1 })
+            );
             assert_eq!(
                 page.handle_input(c).unwrap(),
                 Some(Action::CreateStory { epic_id: 1 })
             );
+            assert_eq!(page.handle_input("z").unwrap(), Some(Action::Undo));
+            assert_eq!(page.handle_input("y").unwrap(), Some(Action::Redo));
             assert_eq!(
                 page.handle_input(&story_id.to_string()).unwrap(),
                 Some(Action::NavigateToStoryDetail {
@@ -335,9 +980,7 @@ mod tests {
 
         #[test]
         fn draw_page_should_not_throw_error() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
 
             let epic_id = db
                 .create_epic(Epic::new("".to_owned(), "".to_owned()))
@@ -350,15 +993,14 @@ mod tests {
                 epic_id,
                 story_id,
                 db,
+                comment_page: 0,
             };
-            assert_eq!(page.draw_page().is_ok(), true);
+            assert!(page.draw_page().is_ok());
         }
 
         #[test]
         fn handle_input_should_not_throw_error() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
 
             let epic_id = db
                 .create_epic(Epic::new("".to_owned(), "".to_owned()))
@@ -371,15 +1013,14 @@ mod tests {
                 epic_id,
                 story_id,
                 db,
+                comment_page: 0,
             };
-            assert_eq!(page.handle_input("").is_ok(), true);
+            assert!(page.handle_input("").is_ok());
         }
 
         #[test]
         fn draw_page_should_throw_error_for_invalid_story_id() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
 
             let epic_id = db
                 .create_epic(Epic::new("".to_owned(), "".to_owned()))
@@ -392,15 +1033,63 @@ mod tests {
                 epic_id,
                 story_id: 999,
                 db,
+                comment_page: 0,
             };
-            assert_eq!(page.draw_page().is_err(), true);
+            assert!(page.draw_page().is_err());
+        }
+
+        #[test]
+        fn handle_input_should_page_through_long_comment_threads() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            for n in 0..=COMMENTS_PER_PAGE {
+                db.add_comment(story_id, format!("comment {}\nsecond line", n))
+                    .unwrap();
+            }
+
+            let page = StoryDetail {
+                epic_id,
+                story_id,
+                db: Rc::clone(&db),
+                comment_page: 0,
+            };
+            assert!(page.draw_page().is_ok());
+            assert_eq!(page.handle_input("<").unwrap(), None);
+            assert_eq!(
+                page.handle_input(">").unwrap(),
+                Some(Action::PageComments {
+                    epic_id,
+                    story_id,
+                    page: 1
+                })
+            );
+
+            let page = StoryDetail {
+                epic_id,
+                story_id,
+                db,
+                comment_page: 1,
+            };
+            assert!(page.draw_page().is_ok());
+            assert_eq!(page.handle_input(">").unwrap(), None);
+            assert_eq!(
+                page.handle_input("<").unwrap(),
+                Some(Action::PageComments {
+                    epic_id,
+                    story_id,
+                    page: 0
+                })
+            );
         }
 
         #[test]
         fn handle_input_should_return_the_correct_actions() {
-            let db = Rc::new(JiraDatabase {
-                database: Box::new(MockDB::new()),
-            });
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
 
             let epic_id = db
                 .create_epic(Epic::new("".to_owned(), "".to_owned()))
@@ -413,6 +1102,7 @@ mod tests {
                 epic_id,
                 story_id,
                 db,
+                comment_page: 0,
             };
 
             let p = "p";
@@ -427,14 +1117,51 @@ mod tests {
                 page.handle_input(p).unwrap(),
                 Some(Action::NavigateToPreviousPage)
             );
+            assert_eq!(
+                page.handle_input("e").unwrap(),
+                Some(Action::UpdateStory { story_id })
+            );
             assert_eq!(
                 page.handle_input(u).unwrap(),
                 Some(Action::UpdateStoryStatus { story_id })
             );
+            assert_eq!(
+                page.handle_input("r").unwrap(),
+                Some(Action::UpdateStoryPriority { story_id })
+            );
+            assert_eq!(
+                page.handle_input("a").unwrap(),
+                Some(Action::AssignStory { story_id })
+            );
+            assert_eq!(
+                page.handle_input("x").unwrap(),
+                Some(Action::UnassignStory { story_id })
+            );
+            assert_eq!(
+                page.handle_input("+").unwrap(),
+                Some(Action::AddStoryLabel { story_id })
+            );
+            assert_eq!(
+                page.handle_input("-").unwrap(),
+                Some(Action::RemoveStoryLabel { story_id })
+            );
             assert_eq!(
                 page.handle_input(d).unwrap(),
                 Some(Action::DeleteStory { epic_id, story_id })
             );
+            assert_eq!(
+                page.handle_input("m").unwrap(),
+                Some(Action::MoveStory { epic_id, story_id })
+            );
+            assert_eq!(
+                page.handle_input("c").unwrap(),
+                Some(Action::AddComment { story_id })
+            );
+            // a single page of comments cannot be paged
+            assert_eq!(page.handle_input("<").unwrap(), None);
+            assert_eq!(page.handle_input(">").unwrap(), None);
+            assert_eq!(page.handle_input("z").unwrap(), Some(Action::Undo));
+            assert_eq!(page.handle_input("y").unwrap(), Some(Action::Redo));
             assert_eq!(page.handle_input(some_number).unwrap(), None);
             assert_eq!(page.handle_input(junk_input).unwrap(), None);
             assert_eq!(
@@ -447,4 +1174,275 @@ mod tests {
             );
         }
     }
+
+    mod my_work_page {
+        use super::*;
+        use crate::models::User;
+
+        #[test]
+        fn draw_page_should_not_throw_error() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+
+            let page = MyWork {
+                user: None,
+                db: Rc::clone(&db),
+            };
+            assert!(page.draw_page().is_ok());
+
+            let page = MyWork {
+                user: Some("alice".to_owned()),
+                db,
+            };
+            assert!(page.draw_page().is_ok());
+        }
+
+        #[test]
+        fn handle_input_should_return_the_correct_actions() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+            db.add_user(User::new("alice".to_owned(), "".to_owned()))
+                .unwrap();
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            db.assign_story(story_id, Some("alice".to_owned())).unwrap();
+
+            let page = MyWork {
+                user: Some("alice".to_owned()),
+                db,
+            };
+
+            assert_eq!(
+                page.handle_input("p").unwrap(),
+                Some(Action::NavigateToPreviousPage)
+            );
+            assert_eq!(
+                page.handle_input(&epic_id.to_string()).unwrap(),
+                Some(Action::NavigateToEpicDetail { epic_id })
+            );
+            assert_eq!(
+                page.handle_input(&story_id.to_string()).unwrap(),
+                Some(Action::NavigateToStoryDetail { epic_id, story_id })
+            );
+            assert_eq!(page.handle_input("z").unwrap(), Some(Action::Undo));
+            assert_eq!(page.handle_input("y").unwrap(), Some(Action::Redo));
+            assert_eq!(page.handle_input("999").unwrap(), None);
+        }
+    }
+
+    mod search_page {
+        use super::*;
+
+        #[test]
+        fn draw_page_should_not_throw_error() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+            db.create_epic(Epic::new("login".to_owned(), "".to_owned()))
+                .unwrap();
+
+            let page = SearchPage {
+                query: "login".to_owned(),
+                db,
+            };
+            assert!(page.draw_page().is_ok());
+        }
+
+        #[test]
+        fn handle_input_should_navigate_to_results_only() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+            let epic_id = db
+                .create_epic(Epic::new("Login".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("login form".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            let other_id = db
+                .create_story(Story::new("other".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+
+            let page = SearchPage {
+                query: "LOGIN".to_owned(),
+                db,
+            };
+
+            assert_eq!(
+                page.handle_input("p").unwrap(),
+                Some(Action::NavigateToPreviousPage)
+            );
+            assert_eq!(
+                page.handle_input(&epic_id.to_string()).unwrap(),
+                Some(Action::NavigateToEpicDetail { epic_id })
+            );
+            assert_eq!(
+                page.handle_input(&story_id.to_string()).unwrap(),
+                Some(Action::NavigateToStoryDetail { epic_id, story_id })
+            );
+            assert_eq!(page.handle_input(&other_id.to_string()).unwrap(), None);
+            assert_eq!(page.handle_input("z").unwrap(), Some(Action::Undo));
+            assert_eq!(page.handle_input("y").unwrap(), Some(Action::Redo));
+            assert_eq!(page.handle_input("j983f2j").unwrap(), None);
+        }
+    }
+
+    mod board_page {
+        use super::*;
+        use std::collections::HashMap;
+
+        #[test]
+        fn draw_page_should_not_throw_error() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("card".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+
+            let page = BoardPage {
+                epic_id: None,
+                db: Rc::clone(&db),
+                selected: Some(story_id),
+            };
+            assert!(page.draw_page().is_ok());
+
+            let page = BoardPage {
+                epic_id: Some(epic_id),
+                db,
+                selected: None,
+            };
+            assert!(page.draw_page().is_ok());
+        }
+
+        #[test]
+        fn draw_page_should_throw_error_for_invalid_epic_id() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+
+            let page = BoardPage {
+                epic_id: Some(999),
+                db,
+                selected: None,
+            };
+            assert!(page.draw_page().is_err());
+        }
+
+        #[test]
+        fn handle_input_should_select_cards_on_the_board() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let other_epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            let other_story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), other_epic_id)
+                .unwrap();
+
+            let page = BoardPage {
+                epic_id: Some(epic_id),
+                db,
+                selected: None,
+            };
+
+            assert_eq!(
+                page.handle_input("p").unwrap(),
+                Some(Action::NavigateToPreviousPage)
+            );
+            assert_eq!(
+                page.handle_input(&story_id.to_string()).unwrap(),
+                Some(Action::SelectCard {
+                    epic_id: Some(epic_id),
+                    story_id
+                })
+            );
+            assert_eq!(
+                page.handle_input(&other_story_id.to_string()).unwrap(),
+                None
+            );
+            assert_eq!(page.handle_input("h").unwrap(), None);
+            assert_eq!(page.handle_input("l").unwrap(), None);
+            assert_eq!(page.handle_input("o").unwrap(), None);
+        }
+
+        #[test]
+        fn handle_input_should_move_the_selected_card() {
+            let db = Rc::new(JiraDatabase::with_database(Box::new(MockDB::new())));
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+
+            let page = BoardPage {
+                epic_id: None,
+                db: Rc::clone(&db),
+                selected: Some(story_id),
+            };
+
+            assert_eq!(page.handle_input("h").unwrap(), None);
+            assert_eq!(
+                page.handle_input("l").unwrap(),
+                Some(Action::MoveCard {
+                    story_id,
+                    status: Status::InProgress
+                })
+            );
+            assert_eq!(
+                page.handle_input("o").unwrap(),
+                Some(Action::NavigateToStoryDetail { epic_id, story_id })
+            );
+
+            db.update_story_status(story_id, Status::Closed).unwrap();
+            assert_eq!(
+                page.handle_input("h").unwrap(),
+                Some(Action::MoveCard {
+                    story_id,
+                    status: Status::Resolved
+                })
+            );
+            assert_eq!(page.handle_input("l").unwrap(), None);
+        }
+
+        #[test]
+        fn handle_input_should_only_move_cards_as_the_workflow_allows() {
+            let mut db = JiraDatabase::with_database(Box::new(MockDB::new()));
+            db.workflow = Workflow {
+                statuses: vec![Status::Open, Status::InProgress, Status::Closed],
+                transitions: HashMap::from([(Status::Closed, vec![])]),
+                ..Workflow::default()
+            };
+            let db = Rc::new(db);
+            let epic_id = db
+                .create_epic(Epic::new("".to_owned(), "".to_owned()))
+                .unwrap();
+            let story_id = db
+                .create_story(Story::new("".to_owned(), "".to_owned()), epic_id)
+                .unwrap();
+            db.update_story_status(story_id, Status::InProgress)
+                .unwrap();
+
+            let page = BoardPage {
+                epic_id: None,
+                db: Rc::clone(&db),
+                selected: Some(story_id),
+            };
+            assert!(page.draw_page().is_ok());
+
+            assert_eq!(
+                page.handle_input("l").unwrap(),
+                Some(Action::MoveCard {
+                    story_id,
+                    status: Status::Closed
+                })
+            );
+
+            db.update_story_status(story_id, Status::Closed).unwrap();
+            assert_eq!(page.handle_input("h").unwrap(), None);
+        }
+    }
 }
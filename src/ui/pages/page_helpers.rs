@@ -1,4 +1,9 @@
+use std::{collections::BTreeSet, ops::Range};
+
+use chrono::{DateTime, Local, Utc};
 use ellipse::Ellipse;
+
+use crate::models::FieldChange;
 pub fn get_column_string(s: &str, width: usize) -> String {
     if s.len() > width {
         if width == 0 {
@@ -20,6 +25,73 @@ pub fn get_column_string(s: &str, width: usize) -> String {
     }
     s.to_string()
 }
+/// Username shown in table columns, `-` for nobody.
+pub fn get_user_string(user: Option<&str>) -> &str {
+    user.unwrap_or("-")
+}
+/// Labels separated by commas, in their sorted order.
+pub fn get_labels_string(labels: &BTreeSet<String>) -> String {
+    labels
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+/// Whether an item with `labels` passes the optional label filter.
+pub fn has_label(labels: &BTreeSet<String>, filter: Option<&str>) -> bool {
+    filter.is_none_or(|label| labels.contains(label))
+}
+/// First line of a possibly multi-line text, used for table columns.
+pub fn get_first_line(s: &str) -> &str {
+    s.lines().next().unwrap_or("")
+}
+/// Indented lines of a multi-line description, `None` if the first line already shows all of it.
+pub fn get_description_block(s: &str) -> Option<String> {
+    if s.lines().count() < 2 {
+        return None;
+    }
+    Some(get_indented(s))
+}
+/// Time in the local timezone to the minute, `-` if unknown.
+pub fn get_time_string(time: Option<DateTime<Utc>>) -> String {
+    match time {
+        Some(time) => time
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M")
+            .to_string(),
+        None => "-".to_owned(),
+    }
+}
+/// One change log entry as `field: old -> new`, or just `created`/`deleted` for the whole item.
+pub fn get_change_string(change: &FieldChange) -> String {
+    if change.old_value.is_none() && change.new_value.is_none() {
+        return change.field.clone();
+    }
+    format!(
+        "{}: {} -> {}",
+        change.field,
+        change.old_value.as_deref().unwrap_or("-"),
+        change.new_value.as_deref().unwrap_or("-")
+    )
+}
+/// Width of each of `columns` board columns separated by ` | `, at least 8 characters.
+pub fn get_board_column_width(terminal_width: usize, columns: usize) -> usize {
+    let separators = columns.saturating_sub(1) * 3;
+    (terminal_width.saturating_sub(separators) / columns.max(1)).max(8)
+}
+/// Indices of page `page` of `len` items counted back from the newest, so page 0 holds the latest
+/// `per_page` items in their original order.
+pub fn get_latest_page(len: usize, page: usize, per_page: usize) -> Range<usize> {
+    let end = len.saturating_sub(page * per_page);
+    end.saturating_sub(per_page)..end
+}
+/// Every line of a text indented, for multi-line blocks below a table row.
+pub fn get_indented(s: &str) -> String {
+    s.lines()
+        .map(|line| format!("    {}", line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -58,4 +130,60 @@ mod tests {
         assert_eq!(get_column_string(text3, width), "testme".to_owned());
         assert_eq!(get_column_string(text4, width), "tes...".to_owned());
     }
+
+    #[test]
+    fn test_labels() {
+        let labels = BTreeSet::from(["ui".to_owned(), "bug".to_owned()]);
+
+        assert_eq!(get_labels_string(&labels), "bug, ui");
+        assert_eq!(get_labels_string(&BTreeSet::new()), "");
+        assert!(has_label(&labels, None));
+        assert!(has_label(&labels, Some("ui")));
+        assert!(!has_label(&labels, Some("backend")));
+    }
+
+    #[test]
+    fn test_get_change_string() {
+        let mut change = FieldChange {
+            item_id: 1,
+            field: "created".to_owned(),
+            old_value: None,
+            new_value: None,
+            time: Utc::now(),
+            actor: None,
+        };
+        assert_eq!(get_change_string(&change), "created");
+
+        change.field = "owner".to_owned();
+        change.new_value = Some("\"alice\"".to_owned());
+        assert_eq!(get_change_string(&change), "owner: - -> \"alice\"");
+        assert_eq!(get_time_string(None), "-");
+    }
+
+    #[test]
+    fn test_get_board_column_width() {
+        assert_eq!(get_board_column_width(80, 4), 17);
+        assert_eq!(get_board_column_width(120, 4), 27);
+        assert_eq!(get_board_column_width(20, 4), 8);
+    }
+
+    #[test]
+    fn test_get_latest_page() {
+        assert_eq!(get_latest_page(12, 0, 5), 7..12);
+        assert_eq!(get_latest_page(12, 1, 5), 2..7);
+        assert_eq!(get_latest_page(12, 2, 5), 0..2);
+        assert_eq!(get_latest_page(3, 0, 5), 0..3);
+        assert_eq!(get_latest_page(0, 0, 5), 0..0);
+    }
+
+    #[test]
+    fn test_get_description_block() {
+        assert_eq!(get_first_line(""), "");
+        assert_eq!(get_first_line("one\ntwo"), "one");
+        assert_eq!(get_description_block("one"), None);
+        assert_eq!(
+            get_description_block("one\n- two"),
+            Some("    one\n    - two".to_owned())
+        );
+    }
 }
@@ -1,14 +1,35 @@
 use crate::{
-    io_utils::get_user_input,
-    models::{Epic, Status, Story},
+    filter::Filter,
+    io_utils::{edit_in_editor, get_user_input},
+    models::{validate_label, Epic, ItemKind, Priority, Status, Story, User},
 };
 
+/// Asks the user to pick one of the given `(id, name)` epics.
+pub type ChooseEpicPrompt = Box<dyn Fn(&[(u32, String)]) -> Option<u32>>;
+/// Asks the user to pick one of the registered users and returns the username.
+pub type ChooseUserPrompt = Box<dyn Fn(&[User]) -> Option<String>>;
+/// Asks the user to pick one of the given allowed next statuses.
+pub type ChooseStatusPrompt = Box<dyn Fn(&[Status]) -> Option<Status>>;
+/// Asks the user to confirm closing an epic with the given stories still open.
+pub type CloseEpicPrompt = Box<dyn Fn(&[u32]) -> bool>;
+
 pub struct Prompts {
     pub create_epic: Box<dyn Fn() -> Epic>,
     pub create_story: Box<dyn Fn() -> Story>,
+    pub update_epic: Box<dyn Fn(&Epic) -> Epic>,
+    pub update_story: Box<dyn Fn(&Story) -> Story>,
     pub delete_epic: Box<dyn Fn() -> bool>,
     pub delete_story: Box<dyn Fn() -> bool>,
-    pub update_status: Box<dyn Fn() -> Option<Status>>,
+    pub close_epic: CloseEpicPrompt,
+    pub update_status: ChooseStatusPrompt,
+    pub update_priority: Box<dyn Fn() -> Option<Priority>>,
+    pub move_story: ChooseEpicPrompt,
+    pub assign: ChooseUserPrompt,
+    pub label: Box<dyn Fn() -> Option<String>>,
+    pub filter_label: Box<dyn Fn() -> Option<String>>,
+    pub filter_query: Box<dyn Fn(ItemKind) -> Option<Filter>>,
+    pub add_comment: Box<dyn Fn() -> Option<String>>,
+    pub search: Box<dyn Fn() -> Option<String>>,
 }
 
 impl Prompts {
@@ -16,9 +37,40 @@ impl Prompts {
         Self {
             create_epic: Box::new(create_epic_prompt),
             create_story: Box::new(create_story_prompt),
+            update_epic: Box::new(update_epic_prompt),
+            update_story: Box::new(update_story_prompt),
             delete_epic: Box::new(delete_epic_prompt),
             delete_story: Box::new(delete_story_prompt),
+            close_epic: Box::new(close_epic_prompt),
             update_status: Box::new(update_status_prompt),
+            update_priority: Box::new(update_priority_prompt),
+            move_story: Box::new(move_story_prompt),
+            assign: Box::new(assign_prompt),
+            label: Box::new(label_prompt),
+            filter_label: Box::new(filter_label_prompt),
+            filter_query: Box::new(filter_query_prompt),
+            add_comment: Box::new(add_comment_prompt),
+            search: Box::new(search_prompt),
+        }
+    }
+}
+
+const OPEN_EDITOR: &str = ":e";
+
+/// Reads a description from the user, or from $VISUAL/$EDITOR opened with `current` if they enter
+/// `:e`. Asks again when the editor fails.
+fn get_description(current: &str) -> String {
+    loop {
+        let input = get_user_input();
+        if input != OPEN_EDITOR {
+            return input;
+        }
+        match edit_in_editor(current) {
+            Ok(description) => return description,
+            Err(error) => println!(
+                "Could not open editor: {:#}. Enter the description or {} to try again:",
+                error, OPEN_EDITOR
+            ),
         }
     }
 }
@@ -27,8 +79,8 @@ fn create_epic_prompt() -> Epic {
     println!("----------------------------");
     println!("Epic Name:");
     let name = get_user_input();
-    println!("Epic Description:");
-    let description = get_user_input();
+    println!("Epic Description ({} to open your editor):", OPEN_EDITOR);
+    let description = get_description("");
     Epic::new(name, description)
 }
 
@@ -36,11 +88,49 @@ fn create_story_prompt() -> Story {
     println!("----------------------------");
     println!("Story Name:");
     let name = get_user_input();
-    println!("Story Description:");
-    let description = get_user_input();
+    println!("Story Description ({} to open your editor):", OPEN_EDITOR);
+    let description = get_description("");
     Story::new(name, description)
 }
 
+fn update_epic_prompt(epic: &Epic) -> Epic {
+    println!("----------------------------");
+    println!("Epic Name (leave empty to keep \"{}\"):", epic.name);
+    let name = get_user_input();
+    println!(
+        "Epic Description (leave empty to keep \"{}\", {} to edit it in your editor):",
+        epic.description, OPEN_EDITOR
+    );
+    let description = get_description(&epic.description);
+    let mut epic = epic.clone();
+    if !name.is_empty() {
+        epic.name = name;
+    }
+    if !description.is_empty() {
+        epic.description = description;
+    }
+    epic
+}
+
+fn update_story_prompt(story: &Story) -> Story {
+    println!("----------------------------");
+    println!("Story Name (leave empty to keep \"{}\"):", story.name);
+    let name = get_user_input();
+    println!(
+        "Story Description (leave empty to keep \"{}\", {} to edit it in your editor):",
+        story.description, OPEN_EDITOR
+    );
+    let description = get_description(&story.description);
+    let mut story = story.clone();
+    if !name.is_empty() {
+        story.name = name;
+    }
+    if !description.is_empty() {
+        story.description = description;
+    }
+    story
+}
+
 fn delete_epic_prompt() -> bool {
     println!("----------------------------");
     println!("Are you sure you want to delete this epic? All stories in this epic will also be deleted [Y/n]:");
@@ -59,14 +149,131 @@ fn delete_story_prompt() -> bool {
     false
 }
 
-fn update_status_prompt() -> Option<Status> {
+fn close_epic_prompt(open_stories: &[u32]) -> bool {
+    println!("----------------------------");
+    let ids: Vec<String> = open_stories.iter().map(u32::to_string).collect();
+    println!(
+        "Warning: stories {} of this epic are still open. Close the epic anyway? [Y/n]:",
+        ids.join(", ")
+    );
+    if get_user_input() == "Y" {
+        return true;
+    }
+    false
+}
+
+fn update_status_prompt(statuses: &[Status]) -> Option<Status> {
+    println!("----------------------------");
+    if statuses.is_empty() {
+        println!("The workflow allows no status changes from the current status.");
+        return None;
+    }
+    let options: Vec<String> = statuses
+        .iter()
+        .enumerate()
+        .map(|(i, status)| format!("{} - {:?}", i + 1, status))
+        .collect();
+    println!("New Status ({}):", options.join(", "));
+    let choice = get_user_input().parse::<usize>().ok()?;
+    statuses.get(choice.checked_sub(1)?).cloned()
+}
+
+fn update_priority_prompt() -> Option<Priority> {
     println!("----------------------------");
-    println!("New Status (1 - OPEN, 2 - IN-PROGRESS, 3 - RESOLVED, 4 - CLOSED):");
+    println!("New Priority (1 - HIGHEST, 2 - HIGH, 3 - MEDIUM, 4 - LOW, 5 - LOWEST):");
     match get_user_input().as_str() {
-        "1" => Some(Status::Open),
-        "2" => Some(Status::InProgress),
-        "3" => Some(Status::Resolved),
-        "4" => Some(Status::Closed),
+        "1" => Some(Priority::Highest),
+        "2" => Some(Priority::High),
+        "3" => Some(Priority::Medium),
+        "4" => Some(Priority::Low),
+        "5" => Some(Priority::Lowest),
         _ => None,
     }
 }
+
+fn move_story_prompt(epics: &[(u32, String)]) -> Option<u32> {
+    println!("----------------------------");
+    println!("Move story to epic:");
+    for (id, name) in epics {
+        println!("{:>6} | {}", id, name);
+    }
+    println!("Epic id (leave empty to cancel):");
+    let epic_id = get_user_input().parse::<u32>().ok()?;
+    epics
+        .iter()
+        .any(|(id, _)| *id == epic_id)
+        .then_some(epic_id)
+}
+
+fn assign_prompt(users: &[User]) -> Option<String> {
+    println!("----------------------------");
+    if users.is_empty() {
+        println!("No users registered yet, add one with the `user add` command.");
+        return None;
+    }
+    println!("Assign to user:");
+    for user in users {
+        println!("{:>16} | {}", user.username, user.name);
+    }
+    println!("Username (leave empty to cancel):");
+    let username = get_user_input();
+    users
+        .iter()
+        .any(|user| user.username == username)
+        .then_some(username)
+}
+
+fn label_prompt() -> Option<String> {
+    println!("----------------------------");
+    println!("Label (leave empty to cancel):");
+    loop {
+        let label = get_user_input();
+        if label.is_empty() {
+            return None;
+        }
+        match validate_label(&label) {
+            Ok(()) => return Some(label),
+            Err(error) => println!("{}, try again (leave empty to cancel):", error),
+        }
+    }
+}
+
+fn filter_label_prompt() -> Option<String> {
+    println!("----------------------------");
+    println!("Only show items with label (leave empty to show all):");
+    let label = get_user_input();
+    (!label.is_empty()).then_some(label)
+}
+
+/// Asks until the expression parses or the input is empty.
+fn filter_query_prompt(kind: ItemKind) -> Option<Filter> {
+    println!("----------------------------");
+    println!("Only show items matching, e.g. status:InProgress name~\"login\" (leave empty to show all):");
+    loop {
+        let input = get_user_input();
+        if input.trim().is_empty() {
+            return None;
+        }
+        match Filter::parse(&input, kind) {
+            Ok(filter) => return Some(filter),
+            Err(error) => println!("Invalid filter: {}\nTry again:", error),
+        }
+    }
+}
+
+fn add_comment_prompt() -> Option<String> {
+    println!("----------------------------");
+    println!(
+        "Comment ({} to open your editor, leave empty to cancel):",
+        OPEN_EDITOR
+    );
+    let comment = get_description("");
+    (!comment.trim().is_empty()).then_some(comment)
+}
+
+fn search_prompt() -> Option<String> {
+    println!("----------------------------");
+    println!("Search names, descriptions, labels and comments (leave empty to cancel):");
+    let query = get_user_input();
+    (!query.trim().is_empty()).then_some(query)
+}
@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use serde::Deserialize;
+
+use crate::models::Status;
+
+/// Statuses that epics and stories can be in and the status changes allowed between them.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Workflow {
+    /// Statuses in use, in the order they are offered and shown on the board.
+    pub statuses: Vec<Status>,
+    /// Allowed next statuses of a status, a status without an entry may change to any other.
+    pub transitions: HashMap<Status, Vec<Status>>,
+    /// Derive the status of an epic from its stories whenever a story changes.
+    pub rollup: bool,
+}
+
+impl Default for Workflow {
+    fn default() -> Self {
+        Self {
+            statuses: Status::ALL.to_vec(),
+            transitions: HashMap::new(),
+            rollup: false,
+        }
+    }
+}
+
+impl Workflow {
+    /// Checks that the workflow only refers to statuses in use, and that new items can start `Open`.
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.statuses.contains(&Status::Open) {
+            return Err(format!(
+                "workflow statuses must include {} for new items",
+                Status::Open
+            ));
+        }
+        for (from, to) in &self.transitions {
+            if let Some(status) = [from]
+                .into_iter()
+                .chain(to)
+                .find(|s| !self.statuses.contains(s))
+            {
+                return Err(format!(
+                    "workflow transition from {} uses status {} that is not in the workflow",
+                    from, status
+                ));
+            }
+        }
+        Ok(())
+    }
+    /// Statuses an item in status `from` may change to, in workflow order.
+    pub fn next_statuses(&self, from: &Status) -> Vec<Status> {
+        self.statuses
+            .iter()
+            .filter(|status| *status != from)
+            .filter(|status| {
+                self.transitions
+                    .get(from)
+                    .is_none_or(|allowed| allowed.contains(status))
+            })
+            .cloned()
+            .collect()
+    }
+    /// Checks that an item may change from status `from` to `to`, keeping its status is always allowed.
+    pub fn check_transition(&self, from: &Status, to: &Status) -> Result<(), String> {
+        if from == to {
+            return Ok(());
+        }
+        let next = self.next_statuses(from);
+        if next.contains(to) {
+            return Ok(());
+        }
+        let allowed = if next.is_empty() {
+            "none".to_owned()
+        } else {
+            next.iter().join(", ")
+        };
+        Err(format!(
+            "cannot change status from {} to {} (allowed: {})",
+            from, to, allowed
+        ))
+    }
+}
+
+/// Status of an epic with stories in the given statuses, `None` for an epic without stories.
+///
+/// All stories closed closes the epic, all resolved or closed resolves it, all open keeps it open
+/// and anything else means work is in progress.
+pub fn rollup_status(statuses: &[Status]) -> Option<Status> {
+    if statuses.is_empty() {
+        return None;
+    }
+    let all = |allowed: &[Status]| statuses.iter().all(|status| allowed.contains(status));
+    Some(if all(&[Status::Closed]) {
+        Status::Closed
+    } else if all(&[Status::Resolved, Status::Closed]) {
+        Status::Resolved
+    } else if all(&[Status::Open]) {
+        Status::Open
+    } else {
+        Status::InProgress
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workflow() -> Workflow {
+        Workflow {
+            statuses: vec![Status::Open, Status::InProgress, Status::Closed],
+            transitions: HashMap::from([
+                (Status::Open, vec![Status::InProgress]),
+                (Status::Closed, vec![]),
+            ]),
+            rollup: false,
+        }
+    }
+
+    #[test]
+    fn default_workflow_should_allow_every_change() {
+        let workflow = Workflow::default();
+
+        assert!(workflow.validate().is_ok());
+        assert_eq!(
+            workflow.next_statuses(&Status::Closed),
+            vec![Status::Open, Status::InProgress, Status::Resolved]
+        );
+        assert!(workflow
+            .check_transition(&Status::Closed, &Status::Open)
+            .is_ok());
+    }
+
+    #[test]
+    fn transitions_should_limit_next_statuses() {
+        let workflow = workflow();
+
+        assert_eq!(
+            workflow.next_statuses(&Status::Open),
+            vec![Status::InProgress]
+        );
+        assert_eq!(
+            workflow.next_statuses(&Status::InProgress),
+            vec![Status::Open, Status::Closed]
+        );
+        assert!(workflow.next_statuses(&Status::Closed).is_empty());
+
+        assert_eq!(
+            workflow.check_transition(&Status::Open, &Status::Closed),
+            Err(
+                "cannot change status from \"Open\" to \"Closed\" (allowed: \"InProgress\")"
+                    .to_owned()
+            )
+        );
+        assert_eq!(
+            workflow.check_transition(&Status::Closed, &Status::Open),
+            Err("cannot change status from \"Closed\" to \"Open\" (allowed: none)".to_owned())
+        );
+        assert_eq!(
+            workflow.check_transition(&Status::InProgress, &Status::Resolved),
+            Err("cannot change status from \"InProgress\" to \"Resolved\" (allowed: \"Open\", \"Closed\")".to_owned())
+        );
+        assert!(workflow
+            .check_transition(&Status::Closed, &Status::Closed)
+            .is_ok());
+    }
+
+    #[test]
+    fn validate_should_reject_unknown_statuses() {
+        let mut workflow = workflow();
+        assert!(workflow.validate().is_ok());
+
+        workflow
+            .transitions
+            .insert(Status::InProgress, vec![Status::Resolved]);
+        assert_eq!(
+            workflow.validate(),
+            Err("workflow transition from \"InProgress\" uses status \"Resolved\" that is not in the workflow".to_owned())
+        );
+
+        workflow.statuses.remove(0);
+        assert!(workflow.validate().is_err());
+    }
+
+    #[test]
+    fn rollup_status_should_follow_the_stories() {
+        use Status::*;
+
+        assert_eq!(rollup_status(&[]), None);
+        assert_eq!(rollup_status(&[Closed, Closed]), Some(Closed));
+        assert_eq!(rollup_status(&[Resolved, Closed]), Some(Resolved));
+        assert_eq!(rollup_status(&[Open, Open]), Some(Open));
+        assert_eq!(rollup_status(&[Open, Closed]), Some(InProgress));
+        assert_eq!(rollup_status(&[InProgress, Open]), Some(InProgress));
+    }
+}